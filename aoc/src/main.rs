@@ -0,0 +1,105 @@
+use std::env;
+use std::path::PathBuf;
+use std::process::{self, Command};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// The command line is: aoc <command> <day> [arguments...]
+//
+// Each command runs the day's binary, which must be built in the same directory as this one (cargo build --workspace).
+// The part is the one the binary was built for.
+
+// Exit status of a day that timed out, matching common::cancel and the `timeout` command
+const TIMED_OUT: i32 = 124;
+
+// How long a day has to stop by itself after its timeout before it is killed
+const GRACE: Duration = Duration::from_secs(1);
+
+const USAGE: &str = "Usage: aoc run <day> <file> [--timeout <seconds>] [options...]
+       aoc check <day> <file>
+       aoc gen <day> [--seed <n>] [--size <n>] [--answer <file>]";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let status = match args.first().map(|s| s.as_str()) {
+        Some("run") if args.len() >= 3 => {
+            let options: Vec<&str> = args[2..].iter().map(|s| s.as_str()).collect();
+            match timeout(&options) {
+                Ok(timeout) => run_day(&args[1], &options, timeout),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    2
+                }
+            }
+        }
+        Some("check") if args.len() == 3 => run_day(&args[1], &[args[2].as_str(), "--check"], None),
+        Some("gen") if args.len() >= 2 => {
+            let options: Vec<&str> = args[2..].iter().map(|s| s.as_str()).collect();
+            run_day(&args[1], &[&["--gen"], options.as_slice()].concat(), None)
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            2
+        }
+    };
+    process::exit(status);
+}
+
+// Returns the timeout given with --timeout in the options, in seconds
+fn timeout(options: &[&str]) -> Result<Option<Duration>, String> {
+    let Some(i) = options.iter().position(|&option| option == "--timeout") else {
+        return Ok(None);
+    };
+    let value = options.get(i + 1).ok_or("Missing --timeout value")?;
+    match value.parse::<f64>() {
+        Ok(seconds) if seconds.is_finite() && seconds >= 0.0 => Ok(Some(Duration::from_secs_f64(seconds))),
+        _ => Err(format!("Invalid --timeout value: {}", value)),
+    }
+}
+
+// Runs a day's binary with the arguments and returns its exit status. The day is passed the timeout and stops itself
+// when its solver checks for cancellation. A day that does not is killed shortly after the timeout.
+fn run_day(day: &str, args: &[&str], timeout: Option<Duration>) -> i32 {
+    let path = day_binary(day).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(2);
+    });
+    let mut child = match Command::new(&path).args(args).spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            eprintln!("Could not run \"{}\": {}", path.display(), e);
+            return 2;
+        }
+    };
+    let deadline = timeout.map(|timeout| Instant::now() + timeout + GRACE);
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return status.code().unwrap_or(1),
+            Ok(None) if deadline.is_some_and(|deadline| Instant::now() >= deadline) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                println!("Result: timed out");
+                return TIMED_OUT;
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(10)),
+            Err(e) => {
+                eprintln!("Could not wait for \"{}\": {}", path.display(), e);
+                return 2;
+            }
+        }
+    }
+}
+
+// Returns the path of a day's binary, given the day's number
+fn day_binary(day: &str) -> Result<PathBuf, String> {
+    let number: u32 = day.parse().map_err(|_| format!("Invalid day: {}", day))?;
+    if !(1..=25).contains(&number) {
+        return Err(format!("Invalid day: {}", day));
+    }
+    let exe = env::current_exe().map_err(|e| format!("Could not find the aoc binary: {}", e))?;
+    let path = exe.with_file_name(format!("day{:02}{}", number, env::consts::EXE_SUFFIX));
+    if !path.exists() {
+        return Err(format!("\"{}\" does not exist. Build it with cargo build -p day{:02}.", path.display(), number));
+    }
+    Ok(path)
+}
@@ -0,0 +1,23 @@
+use std::env;
+
+// The command line is: <day> <input file> [options...]
+
+/// Returns the path of the input file, which is the first argument
+pub fn path() -> Result<String, String> {
+    env::args()
+        .nth(1)
+        .filter(|arg| !arg.starts_with('-'))
+        .ok_or_else(|| "Missing input file argument".to_string())
+}
+
+/// Returns true if the option was given on the command line
+pub fn flag(name: &str) -> bool {
+    env::args().skip(1).any(|arg| arg == name)
+}
+
+/// Returns the value following an option on the command line, if the option was given
+pub fn value(name: &str) -> Option<String> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let i = args.iter().position(|arg| arg == name)?;
+    args.get(i + 1).cloned()
+}
@@ -0,0 +1,240 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// A signed integer of any size
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BigInt {
+    negative: bool,
+    // Base 2^32 digits, least significant first, with no leading zeros. Zero has no digits and is not negative.
+    digits: Vec<u32>,
+}
+
+impl BigInt {
+    /// Returns zero
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if the number is zero
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Returns true if the number is less than zero
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Divides by a small number, returning the quotient and remainder. Like i64 division, the quotient is rounded
+    /// toward zero and the remainder has the sign of the number. The divisor must not be 0.
+    pub fn div_rem_small(&self, divisor: u32) -> (Self, i64) {
+        assert!(divisor != 0, "Division by zero");
+        let mut quotient = vec![0; self.digits.len()];
+        let mut remainder: u64 = 0;
+        for (i, &digit) in self.digits.iter().enumerate().rev() {
+            let value = (remainder << 32) | digit as u64;
+            quotient[i] = (value / divisor as u64) as u32;
+            remainder = value % divisor as u64;
+        }
+        let remainder = if self.negative { -(remainder as i64) } else { remainder as i64 };
+        (Self::from_parts(self.negative, quotient), remainder)
+    }
+
+    /// Returns the number as an i64, or None if it does not fit
+    pub fn to_i64(&self) -> Option<i64> {
+        if self.digits.len() > 2 {
+            return None;
+        }
+        let magnitude = self.digits.iter().rev().fold(0u64, |value, &digit| (value << 32) | digit as u64);
+        if self.negative {
+            0i64.checked_sub_unsigned(magnitude)
+        } else {
+            i64::try_from(magnitude).ok()
+        }
+    }
+
+    // Creates a number from its sign and digits, removing leading zeros
+    fn from_parts(negative: bool, mut digits: Vec<u32>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        Self { negative: negative && !digits.is_empty(), digits }
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        let magnitude = value.unsigned_abs();
+        Self::from_parts(value < 0, vec![magnitude as u32, (magnitude >> 32) as u32])
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        Self::from_parts(!self.negative, self.digits)
+    }
+}
+
+impl Add for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, add_magnitudes(&self.digits, &other.digits));
+        }
+        match compare_magnitudes(&self.digits, &other.digits) {
+            Ordering::Less => BigInt::from_parts(other.negative, subtract_magnitudes(&other.digits, &self.digits)),
+            _ => BigInt::from_parts(self.negative, subtract_magnitudes(&self.digits, &other.digits)),
+        }
+    }
+}
+
+impl Sub for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &-other.clone()
+    }
+}
+
+impl Mul for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        let mut digits = vec![0u32; self.digits.len() + other.digits.len()];
+        for (i, &a) in self.digits.iter().enumerate() {
+            let mut carry: u64 = 0;
+            for (j, &b) in other.digits.iter().enumerate() {
+                let value = digits[i + j] as u64 + a as u64 * b as u64 + carry;
+                digits[i + j] = value as u32;
+                carry = value >> 32;
+            }
+            digits[i + other.digits.len()] = carry as u32;
+        }
+        BigInt::from_parts(self.negative != other.negative, digits)
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Convert to base 10^9, least significant first
+        const BASE: u32 = 1_000_000_000;
+        let mut chunks = Vec::new();
+        let mut rest = BigInt::from_parts(false, self.digits.clone());
+        while !rest.is_zero() {
+            let (quotient, remainder) = rest.div_rem_small(BASE);
+            chunks.push(remainder);
+            rest = quotient;
+        }
+        let mut text = if self.negative { "-".to_string() } else { String::new() };
+        match chunks.split_last() {
+            Some((most, others)) => {
+                text += &most.to_string();
+                for chunk in others.iter().rev() {
+                    text += &format!("{:09}", chunk);
+                }
+            }
+            None => text += "0",
+        }
+        f.pad(&text)
+    }
+}
+
+fn compare_magnitudes(a: &[u32], b: &[u32]) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut digits = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry: u64 = 0;
+    for i in 0..a.len().max(b.len()) {
+        let value = *a.get(i).unwrap_or(&0) as u64 + *b.get(i).unwrap_or(&0) as u64 + carry;
+        digits.push(value as u32);
+        carry = value >> 32;
+    }
+    digits.push(carry as u32);
+    digits
+}
+
+// Returns a - b, where a is at least b
+fn subtract_magnitudes(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut digits = Vec::with_capacity(a.len());
+    let mut borrow: i64 = 0;
+    for (i, &digit) in a.iter().enumerate() {
+        let mut value = digit as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        borrow = 0;
+        if value < 0 {
+            value += 1 << 32;
+            borrow = 1;
+        }
+        digits.push(value as u32);
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(value: i64) -> BigInt {
+        BigInt::from(value)
+    }
+
+    #[test]
+    fn add_and_sub_across_signs() {
+        assert_eq!((&big(5) + &big(-7)).to_string(), "-2");
+        assert_eq!((&big(-5) + &big(7)).to_string(), "2");
+        assert_eq!((&big(-5) + &big(-7)).to_string(), "-12");
+        assert_eq!((&big(5) - &big(-7)).to_string(), "12");
+        assert_eq!((&big(-5) - &big(7)).to_string(), "-12");
+        assert_eq!((&big(-5) - &big(-5)).to_string(), "0");
+        assert!(!(&big(-5) - &big(-5)).is_negative());
+        assert!((&big(5) + &big(-5)).is_zero());
+        // Carries and borrows across chunks
+        assert_eq!((&big(u32::MAX as i64) + &big(1)).to_i64(), Some(1 << 32));
+        assert_eq!((&big(1 << 32) - &big(1)).to_i64(), Some(u32::MAX as i64));
+    }
+
+    #[test]
+    fn mul() {
+        assert_eq!((&big(6) * &big(-7)).to_string(), "-42");
+        assert_eq!((&big(-6) * &big(-7)).to_string(), "42");
+        assert!(!(&big(-6) * &BigInt::zero()).is_negative());
+        let n = big((1 << 32) + 1);
+        assert_eq!((&n * &n).to_string(), "18446744082299486209");
+        assert_eq!((&big(i64::MIN) * &big(i64::MIN)).to_string(), "85070591730234615865843651857942052864");
+    }
+
+    #[test]
+    fn div_rem_small() {
+        let (q, r) = big(7).div_rem_small(2);
+        assert_eq!((q.to_i64(), r), (Some(3), 1));
+        let (q, r) = big(-7).div_rem_small(2);
+        assert_eq!((q.to_i64(), r), (Some(-3), -1));
+        let (q, r) = big(-1).div_rem_small(2);
+        assert_eq!((q.to_i64(), r), (Some(0), -1));
+        assert!(!q.is_negative());
+        let (q, r) = big(i64::MIN).div_rem_small(3);
+        assert_eq!((q.to_i64(), r), (Some(i64::MIN / 3), i64::MIN % 3));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(BigInt::zero().to_string(), "0");
+        assert_eq!(big(1_000_000_000_000_000_005).to_string(), "1000000000000000005");
+        assert_eq!(big(-1_000_000_000).to_string(), "-1000000000");
+        assert_eq!(big(-1_000_000_007_000_000_000).to_string(), "-1000000007000000000");
+        assert_eq!(format!("{:>6}", big(-42)), "   -42");
+    }
+
+    #[test]
+    fn to_i64_bounds() {
+        assert_eq!(big(i64::MAX).to_i64(), Some(i64::MAX));
+        assert_eq!(big(i64::MIN).to_i64(), Some(i64::MIN));
+        assert_eq!((&big(i64::MAX) + &big(1)).to_i64(), None);
+        assert_eq!((&big(i64::MIN) - &big(1)).to_i64(), None);
+    }
+}
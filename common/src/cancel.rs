@@ -0,0 +1,101 @@
+use crate::args;
+use std::fmt;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Exit status used when a solver is cancelled, matching the `timeout` command
+pub const EXIT_STATUS: i32 = 124;
+
+/// The error returned by a solver that stopped because it was cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "timed out")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// A cancellation token that is cancelled explicitly or when its deadline passes. Clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct Token {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl Token {
+    /// Creates a token that is only cancelled explicitly
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a token that is cancelled after the timeout
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            deadline: Some(Instant::now() + timeout),
+            ..Self::default()
+        }
+    }
+
+    /// Cancels the token
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if the token has been cancelled or its deadline has passed
+    pub fn is_cancelled(&self) -> bool {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return true;
+        }
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.cancel();
+            return true;
+        }
+        false
+    }
+
+    /// Returns Err(Cancelled) if the token has been cancelled
+    pub fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Starts the timeout of the process-wide token. Days with cancellable solvers call this first in main, so that the
+/// timeout covers loading and parsing the input as well as solving it.
+pub fn start() {
+    token();
+}
+
+/// Returns the process-wide token. Its timeout is given in seconds with --timeout, and it has no timeout otherwise. The
+/// timeout starts when start is called, or when the token is first used if it is not.
+pub fn token() -> &'static Token {
+    static TOKEN: OnceLock<Token> = OnceLock::new();
+    TOKEN.get_or_init(|| match args::value("--timeout") {
+        Some(seconds) => {
+            let seconds: f64 = seconds
+                .parse()
+                .unwrap_or_else(|_| panic!("Invalid --timeout value: {}", seconds));
+            Token::with_timeout(Duration::from_secs_f64(seconds))
+        }
+        None => Token::new(),
+    })
+}
+
+/// Returns Err(Cancelled) if the process-wide token has been cancelled. Solvers call this in their main loops.
+pub fn check() -> Result<(), Cancelled> {
+    token().check()
+}
+
+/// Reports that the solver was cancelled and exits. It is generic so that it can be passed to `unwrap_or_else`.
+pub fn exit<T>(cancelled: Cancelled) -> T {
+    println!("Result: {}", cancelled);
+    process::exit(EXIT_STATUS);
+}
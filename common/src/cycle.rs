@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// The shape of a sequence of states produced by repeatedly applying a transition to an initial state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// Number of steps before the sequence enters the cycle
+    pub start: usize,
+    /// Number of steps in one repetition of the cycle
+    pub period: usize,
+}
+
+impl Cycle {
+    /// Returns the earliest step that has the same state as step n
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+}
+
+/// The states visited while detecting a cycle
+#[derive(Debug)]
+pub struct History<T> {
+    pub cycle: Cycle,
+    /// Every state from the initial state through the end of the first repetition of the cycle
+    pub states: Vec<T>,
+}
+
+impl<T> History<T> {
+    /// Returns the state after n steps
+    pub fn nth(&self, n: usize) -> &T {
+        &self.states[self.cycle.reduce(n)]
+    }
+}
+
+/// Detects a cycle by hashing every state visited. This is fast and records the history so the state at any step can
+/// be extrapolated, but it stores every state until the cycle repeats.
+pub fn detect<T, F>(initial: T, mut step: F) -> History<T>
+where
+    T: Clone + Eq + Hash,
+    F: FnMut(&T) -> T,
+{
+    let mut seen: HashMap<T, usize> = HashMap::new();
+    let mut states: Vec<T> = Vec::new();
+    let mut state = initial;
+    loop {
+        if let Some(&start) = seen.get(&state) {
+            let period = states.len() - start;
+            return History {
+                cycle: Cycle { start, period },
+                states,
+            };
+        }
+        let next = step(&state);
+        seen.insert(state.clone(), states.len());
+        states.push(state);
+        state = next;
+    }
+}
+
+/// Detects a cycle using Brent's algorithm. Only a couple of states are held at a time, but the transition is applied
+/// roughly three times as often as with `detect`.
+pub fn brent<T, F>(initial: T, mut step: F) -> Cycle
+where
+    T: Clone + Eq,
+    F: FnMut(&T) -> T,
+{
+    // Find the period by advancing the hare in ever larger powers of two until it meets the tortoise
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // Find the start by running two states separated by the period until they meet
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, period }
+}
+
+/// Returns the state after n steps, skipping over whole repetitions of the cycle once it is found
+pub fn nth<T, F>(initial: T, step: F, n: usize) -> T
+where
+    T: Clone + Eq + Hash,
+    F: FnMut(&T) -> T,
+{
+    detect(initial, step).nth(n).clone()
+}
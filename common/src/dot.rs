@@ -0,0 +1,69 @@
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A graph that is written in the Graphviz DOT language
+#[derive(Debug)]
+pub struct Graph {
+    directed: bool,
+    nodes: Vec<String>,
+    edges: Vec<String>,
+}
+
+impl Graph {
+    /// Creates a graph whose edges have a direction
+    pub fn directed() -> Self {
+        Self {
+            directed: true,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Creates a graph whose edges have no direction
+    pub fn undirected() -> Self {
+        Self {
+            directed: false,
+            ..Self::directed()
+        }
+    }
+
+    /// Adds a node with attributes such as ("shape", "box")
+    pub fn node(&mut self, name: &str, attributes: &[(&str, &str)]) {
+        self.nodes.push(format!("{}{}", quote(name), format_attributes(attributes)));
+    }
+
+    /// Adds an edge with attributes such as ("color", "red")
+    pub fn edge(&mut self, from: &str, to: &str, attributes: &[(&str, &str)]) {
+        let connector = if self.directed { "->" } else { "--" };
+        self.edges.push(format!("{} {} {}{}", quote(from), connector, quote(to), format_attributes(attributes)));
+    }
+
+    /// Returns the graph as a DOT document
+    pub fn to_dot(&self) -> String {
+        let mut dot = format!("{} {{\n", if self.directed { "digraph" } else { "graph" });
+        for statement in self.nodes.iter().chain(self.edges.iter()) {
+            writeln!(dot, "    {};", statement).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Writes the graph to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_dot())
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn format_attributes(attributes: &[(&str, &str)]) -> String {
+    if attributes.is_empty() {
+        return String::new();
+    }
+    let attributes: Vec<String> = attributes.iter().map(|(key, value)| format!("{}={}", key, quote(value))).collect();
+    format!(" [{}]", attributes.join(", "))
+}
@@ -0,0 +1,92 @@
+use crate::args;
+use crate::render::Overlay;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Records the steps of a simulation as a numbered sequence of PPM images in a directory
+#[derive(Debug)]
+pub struct Recorder {
+    directory: PathBuf,
+    scale: usize,
+    palette: HashMap<char, [u8; 3]>,
+    count: usize,
+}
+
+impl Recorder {
+    /// Creates a recorder that writes to the directory, creating the directory if necessary
+    pub fn new(directory: impl Into<PathBuf>) -> io::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+        let palette = HashMap::from([('.', [16, 16, 16]), ('#', [128, 128, 128]), ('O', [238, 238, 238])]);
+        Ok(Self {
+            directory,
+            scale: 4,
+            palette,
+            count: 0,
+        })
+    }
+
+    /// Creates a recorder for the directory given with --frames, or returns None if the option was not given
+    pub fn from_args() -> Option<Self> {
+        let directory = args::value("--frames")?;
+        Some(Self::new(&directory).unwrap_or_else(|e| panic!("Could not create \"{}\": {}", directory, e)))
+    }
+
+    /// Sets the number of pixels on each side of a cell
+    pub fn with_scale(mut self, scale: usize) -> Self {
+        self.scale = scale.max(1);
+        self
+    }
+
+    /// Sets the color of cells containing the character
+    pub fn with_color(mut self, c: char, rgb: [u8; 3]) -> Self {
+        self.palette.insert(c, rgb);
+        self
+    }
+
+    /// Returns the number of frames recorded so far
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Writes the grid and its overlays as the next frame. Later overlays are drawn over earlier ones.
+    pub fn record(&mut self, grid: &[Vec<char>], overlays: &[Overlay]) -> io::Result<()> {
+        let height = grid.len();
+        let width = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+
+        let mut pixels: Vec<u8> = Vec::with_capacity(width * height * self.scale * self.scale * 3);
+        for (y, row) in grid.iter().enumerate() {
+            let colors: Vec<[u8; 3]> = (0..width)
+                .map(|x| match overlays.iter().rev().find(|overlay| overlay.cells.contains(&(x, y))) {
+                    Some(overlay) => overlay.color.rgb(),
+                    None => row.get(x).map_or([0, 0, 0], |&c| self.color_of(c)),
+                })
+                .collect();
+            for _ in 0..self.scale {
+                for rgb in &colors {
+                    for _ in 0..self.scale {
+                        pixels.extend_from_slice(rgb);
+                    }
+                }
+            }
+        }
+
+        let path = self.directory.join(format!("frame{:05}.ppm", self.count));
+        let mut data = format!("P6\n{} {}\n255\n", width * self.scale, height * self.scale).into_bytes();
+        data.extend(pixels);
+        fs::write(path, data)?;
+        self.count += 1;
+        Ok(())
+    }
+
+    // Characters not in the palette get an arbitrary but consistent color
+    fn color_of(&self, c: char) -> [u8; 3] {
+        if let Some(&rgb) = self.palette.get(&c) {
+            return rgb;
+        }
+        let hash = (c as u32).wrapping_mul(2654435761);
+        [(hash >> 24) as u8 | 0x40, (hash >> 16) as u8 | 0x40, (hash >> 8) as u8 | 0x40]
+    }
+}
@@ -0,0 +1,198 @@
+use crate::args;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::process;
+
+/// A small pseudo-random number generator (SplitMix64). The same seed always produces the same sequence, so generated
+/// inputs can be reproduced.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a generator from a seed
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next 64 random bits
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in 0..n. n must not be 0.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0, "Empty range");
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Returns a number in lo..=hi
+    pub fn between(&mut self, lo: i64, hi: i64) -> i64 {
+        assert!(lo <= hi, "Empty range");
+        lo + (self.next_u64() % (hi - lo + 1) as u64) as i64
+    }
+
+    /// Returns true with the probability p
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    /// Returns a random item. The slice must not be empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    /// Shuffles the items in place
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+
+    /// Returns a string of random characters from the alphabet
+    pub fn string(&mut self, length: usize, alphabet: &str) -> String {
+        let alphabet: Vec<char> = alphabet.chars().collect();
+        (0..length).map(|_| *self.choose(&alphabet)).collect()
+    }
+
+    /// Returns distinct random strings of characters from the alphabet, none of which are in `exclude`
+    pub fn names(&mut self, count: usize, length: usize, alphabet: &str, exclude: &[&str]) -> Vec<String> {
+        let mut names: HashSet<String> = exclude.iter().map(|name| name.to_string()).collect();
+        let mut result = Vec::new();
+        while result.len() < count {
+            let name = self.string(length, alphabet);
+            if names.insert(name.clone()) {
+                result.push(name);
+            }
+        }
+        result
+    }
+}
+
+/// Writes a generated input to stdout and exits if --gen is given.
+///
+/// The generator is given a random number generator seeded with --seed (0 by default) and the size given with --size
+/// (or the default size). What the size means is up to each day, such as the number of lines or the width of a grid.
+pub fn run(default_size: usize, generate: impl Fn(&mut Rng, usize) -> Vec<String>) {
+    if let Some(lines) = generated(default_size, generate) {
+        if args::value("--answer").is_some() {
+            panic!("This day's generator has no --answer");
+        }
+        write(&lines);
+    }
+}
+
+/// Like run, and also writes the expected answer for the generated input to the file given with --answer. The answer
+/// is computed from the generated lines, independently of the day's solver.
+pub fn run_with_answer(
+    default_size: usize,
+    generate: impl Fn(&mut Rng, usize) -> Vec<String>,
+    answer: impl Fn(&[String]) -> Vec<String>,
+) {
+    if let Some(lines) = generated(default_size, generate) {
+        if let Some(path) = args::value("--answer") {
+            let contents: String = answer(&lines).iter().map(|line| format!("{}\n", line)).collect();
+            fs::write(&path, contents).unwrap_or_else(|e| panic!("Could not write \"{}\": {}", path, e));
+        }
+        write(&lines);
+    }
+}
+
+// Returns the generated lines if --gen is given
+fn generated(default_size: usize, generate: impl Fn(&mut Rng, usize) -> Vec<String>) -> Option<Vec<String>> {
+    if !args::flag("--gen") {
+        return None;
+    }
+    let seed: u64 = args::value("--seed")
+        .map(|s| s.parse().unwrap_or_else(|_| panic!("Invalid --seed value: {}", s)))
+        .unwrap_or(0);
+    let size: usize = args::value("--size")
+        .map(|s| s.parse().unwrap_or_else(|_| panic!("Invalid --size value: {}", s)))
+        .unwrap_or(default_size);
+    Some(generate(&mut Rng::new(seed), size))
+}
+
+// Writes the generated lines to stdout and exits
+fn write(lines: &[String]) {
+    let mut out = BufWriter::new(io::stdout().lock());
+    for line in lines {
+        writeln!(out, "{}", line).unwrap();
+    }
+    out.flush().unwrap();
+    process::exit(0);
+}
+
+/// Returns the vertices of a random simple loop on the lattice points (0, 0) to (width, height), in order. Each vertex
+/// is one step from the next, and the loop does not touch itself.
+///
+/// The loop is the outline of a random region of cells grown from the center, where a cell is only added if the outline
+/// would not touch itself at a corner.
+pub fn random_loop(rng: &mut Rng, width: usize, height: usize) -> Vec<(i64, i64)> {
+    assert!(width >= 1 && height >= 1, "The loop needs at least one cell");
+    let filled_at = |cells: &Vec<Vec<bool>>, x: i64, y: i64| {
+        x >= 0 && y >= 0 && (x as usize) < width && (y as usize) < height && cells[y as usize][x as usize]
+    };
+
+    // Grow the region
+    let mut cells = vec![vec![false; width]; height];
+    let mut region = vec![(width as i64 / 2, height as i64 / 2)];
+    cells[height / 2][width / 2] = true;
+    let target = (width * height * 3 / 5).max(1);
+    for _ in 0..width * height * 8 {
+        if region.len() >= target {
+            break;
+        }
+        let (x, y) = *rng.choose(&region);
+        let (dx, dy) = *rng.choose(&[(1, 0), (-1, 0), (0, 1), (0, -1)]);
+        let (nx, ny) = (x + dx, y + dy);
+        if nx < 0 || ny < 0 || nx as usize >= width || ny as usize >= height || cells[ny as usize][nx as usize] {
+            continue;
+        }
+        cells[ny as usize][nx as usize] = true;
+        // A corner where only diagonally opposite cells are filled would be touched twice by the outline
+        let pinched = [(nx, ny), (nx + 1, ny), (nx, ny + 1), (nx + 1, ny + 1)].iter().any(|&(vx, vy)| {
+            let nw = filled_at(&cells, vx - 1, vy - 1);
+            let ne = filled_at(&cells, vx, vy - 1);
+            let sw = filled_at(&cells, vx - 1, vy);
+            let se = filled_at(&cells, vx, vy);
+            nw == se && ne == sw && nw != ne
+        });
+        if pinched {
+            cells[ny as usize][nx as usize] = false;
+        } else {
+            region.push((nx, ny));
+        }
+    }
+
+    // Trace the outline clockwise from the top-left corner of the topmost, leftmost cell
+    let start = region.iter().map(|&(x, y)| (y, x)).min().map(|(y, x)| (x, y)).unwrap();
+    let is_edge = |(x0, y0): (i64, i64), (x1, y1): (i64, i64)| {
+        if y0 == y1 {
+            let x = x0.min(x1);
+            filled_at(&cells, x, y0 - 1) != filled_at(&cells, x, y0)
+        } else {
+            let y = y0.min(y1);
+            filled_at(&cells, x0 - 1, y) != filled_at(&cells, x0, y)
+        }
+    };
+    let mut vertices = vec![start];
+    let mut previous = start;
+    let mut current = (start.0 + 1, start.1);
+    while current != start {
+        vertices.push(current);
+        let next = [(1, 0), (0, 1), (-1, 0), (0, -1)]
+            .iter()
+            .map(|&(dx, dy)| (current.0 + dx, current.1 + dy))
+            .find(|&next| next != previous && is_edge(current, next))
+            .unwrap();
+        previous = current;
+        current = next;
+    }
+    vertices
+}
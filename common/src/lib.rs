@@ -1 +1,17 @@
+pub mod args;
+pub mod bigint;
+pub mod cancel;
+pub mod cycle;
+pub mod dot;
+pub mod frames;
+pub mod generate;
 pub mod load;
+pub mod log;
+pub mod math;
+pub mod memo;
+pub mod polygon;
+pub mod progress;
+pub mod render;
+pub mod svg;
+pub mod validate;
+pub mod verify;
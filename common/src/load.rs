@@ -1,4 +1,5 @@
-use std::{env, fs::read_to_string, io, path::Path};
+use crate::args;
+use std::{fs::read_to_string, io, path::Path};
 
 /// Loads lines of data from the file specified in the command into a vector of strings
 pub fn lines() -> Result<Vec<String>, String> {
@@ -8,8 +9,12 @@ pub fn lines() -> Result<Vec<String>, String> {
 
 /// Reads a file line by line into a vector of strings
 fn read_lines(filename: impl AsRef<Path>) -> io::Result<Vec<String>> {
-    let input = read_to_string(filename)?;
-    Ok(input.lines().map(|line| line.to_string()).collect())
+    Ok(parse_lines(&read_to_string(filename)?))
+}
+
+/// Splits text into a vector of lines
+pub fn parse_lines(input: &str) -> Vec<String> {
+    input.lines().map(|line| line.to_string()).collect()
 }
 
 /// Loads a file into a vector of strings separated by ','.
@@ -20,8 +25,12 @@ pub fn comma_separated_values() -> Result<Vec<String>, String> {
 
 /// Reads an entire file into a string and splits it by ',' into a vector of strings
 fn read_comma_separated_values(filename: impl AsRef<Path>) -> io::Result<Vec<String>> {
-    let input = read_to_string(filename)?;
-    Ok(input.split(',').map(|s| s.trim().to_string()).collect())
+    Ok(parse_comma_separated_values(&read_to_string(filename)?))
+}
+
+/// Splits text by ',' into a vector of trimmed strings
+pub fn parse_comma_separated_values(input: &str) -> Vec<String> {
+    input.split(',').map(|s| s.trim().to_string()).collect()
 }
 
 /// Loads a file into a 2D array of characters
@@ -32,8 +41,12 @@ pub fn map() -> Result<Vec<Vec<char>>, String> {
 
 /// Reads an entire file into a 2D array of characters
 fn read_map(filename: impl AsRef<Path>) -> io::Result<Vec<Vec<char>>> {
-    let input = read_to_string(filename)?;
-    Ok(input.lines().map(|line| line.chars().collect()).collect())
+    Ok(parse_map(&read_to_string(filename)?))
+}
+
+/// Splits text into a 2D array of characters
+pub fn parse_map(input: &str) -> Vec<Vec<char>> {
+    input.lines().map(|line| line.chars().collect()).collect()
 }
 
 /// Loads a file into a 2D array of numbers
@@ -44,14 +57,17 @@ pub fn numbers_map() -> Result<Vec<Vec<i32>>, String> {
 
 /// Reads an entire file into a 2D array of numbers
 fn read_numbers_map(filename: impl AsRef<Path>) -> io::Result<Vec<Vec<i32>>> {
-    let input = read_to_string(filename)?;
-    Ok(input
+    Ok(parse_numbers_map(&read_to_string(filename)?))
+}
+
+/// Splits text into a 2D array of numbers, one for each digit. Other characters are ignored.
+pub fn parse_numbers_map(input: &str) -> Vec<Vec<i32>> {
+    input
         .lines()
         .map(|line| line.chars().filter_map(|c| c.to_digit(10).map(|d| d as i32)).collect())
-        .collect())
+        .collect()
 }
 
 fn get_path() -> Result<String, String> {
-    let args: Vec<String> = env::args().collect();
-    args.get(1).cloned().ok_or_else(|| "Missing input file argument".to_string())
+    args::path()
 }
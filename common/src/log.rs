@@ -0,0 +1,126 @@
+use crate::args;
+use std::env;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Logging levels, from least to most verbose
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    fn parse(s: &str) -> Option<Option<Level>> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Some(None),
+            "error" => Some(Some(Level::Error)),
+            "warn" => Some(Some(Level::Warn)),
+            "info" => Some(Some(Level::Info)),
+            "debug" => Some(Some(Level::Debug)),
+            "trace" => Some(Some(Level::Trace)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Returns the most verbose level that is logged, or None if logging is off.
+///
+/// Each -v on the command line (or the v's in -vv and -vvv) raises the level above the default of Warn. Otherwise,
+/// RUST_LOG may be set to off, error, warn, info, debug, or trace.
+pub fn level() -> Option<Level> {
+    static LEVEL: OnceLock<Option<Level>> = OnceLock::new();
+    *LEVEL.get_or_init(|| {
+        let verbosity: usize = env::args()
+            .skip(1)
+            .filter(|arg| arg.len() > 1 && arg.starts_with('-') && arg[1..].chars().all(|c| c == 'v'))
+            .map(|arg| arg.len() - 1)
+            .sum::<usize>()
+            + if args::flag("--verbose") { 1 } else { 0 };
+        match verbosity {
+            0 => env::var("RUST_LOG")
+                .ok()
+                .and_then(|value| Level::parse(&value))
+                .unwrap_or(Some(Level::Warn)),
+            1 => Some(Level::Info),
+            2 => Some(Level::Debug),
+            _ => Some(Level::Trace),
+        }
+    })
+}
+
+/// Returns true if messages at the level are logged
+pub fn enabled(level: Level) -> bool {
+    self::level().is_some_and(|max| level <= max)
+}
+
+/// Writes a message to stderr. Use the error!, warn!, info!, debug!, and trace! macros instead.
+pub fn write(level: Level, args: fmt::Arguments) {
+    eprintln!("[{}] {}", level, args);
+}
+
+/// Logs a message at the Error level
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Error) {
+            $crate::log::write($crate::log::Level::Error, format_args!($($arg)*));
+        }
+    };
+}
+
+/// Logs a message at the Warn level
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Warn) {
+            $crate::log::write($crate::log::Level::Warn, format_args!($($arg)*));
+        }
+    };
+}
+
+/// Logs a message at the Info level
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Info) {
+            $crate::log::write($crate::log::Level::Info, format_args!($($arg)*));
+        }
+    };
+}
+
+/// Logs a message at the Debug level
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Debug) {
+            $crate::log::write($crate::log::Level::Debug, format_args!($($arg)*));
+        }
+    };
+}
+
+/// Logs a message at the Trace level
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Trace) {
+            $crate::log::write($crate::log::Level::Trace, format_args!($($arg)*));
+        }
+    };
+}
@@ -0,0 +1,172 @@
+/// Returns the greatest common divisor of two numbers. The result is always non-negative.
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let mut a = a.abs();
+    let mut b = b.abs();
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Returns the least common multiple of two numbers, or None if it does not fit in an i64. The result is always
+/// non-negative.
+pub fn lcm(a: i64, b: i64) -> Option<i64> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)?.checked_abs()
+}
+
+/// Returns the greatest common divisor of all the numbers, or 0 if there are none
+pub fn gcd_of(values: impl IntoIterator<Item = i64>) -> i64 {
+    values.into_iter().fold(0, gcd)
+}
+
+/// Returns the least common multiple of all the numbers, 1 if there are none, or None if it does not fit in an i64
+pub fn lcm_of(values: impl IntoIterator<Item = i64>) -> Option<i64> {
+    values.into_iter().try_fold(1, lcm)
+}
+
+/// Extended Euclidean algorithm. Returns (g, x, y) such that a * x + b * y = g, where g = gcd(a, b).
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (g, x, y) = extended_gcd_i128(a as i128, b as i128);
+    (g as i64, x as i64, y as i64)
+}
+
+fn extended_gcd_i128(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (1, 0);
+    let (mut old_t, mut t) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+        (old_t, t) = (t, old_t - q * t);
+    }
+    if old_r < 0 {
+        (-old_r, -old_s, -old_t)
+    } else {
+        (old_r, old_s, old_t)
+    }
+}
+
+/// Returns the inverse of a modulo m, or None if a and m are not coprime
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = extended_gcd_i128(a as i128, m as i128);
+    if g != 1 {
+        return None;
+    }
+    Some(x.rem_euclid(m as i128) as i64)
+}
+
+/// Solves a system of congruences x ≡ r (mod m) given as (r, m) pairs using the Chinese Remainder Theorem.
+///
+/// The moduli do not need to be coprime. Returns (x, lcm) where x is the smallest non-negative solution and every
+/// solution is x + k * lcm, or None if the congruences are inconsistent or the result does not fit in an i64.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut x: i128 = 0;
+    let mut m: i128 = 1;
+    for &(r, n) in congruences {
+        if n <= 0 {
+            return None;
+        }
+        let (r, n) = ((r as i128).rem_euclid(n as i128), n as i128);
+
+        // Find k such that x + m * k ≡ r (mod n)
+        let (g, p, _) = extended_gcd_i128(m, n);
+        let difference = r - x;
+        if difference % g != 0 {
+            return None;
+        }
+        let step = n / g;
+        let k = (difference / g % step * p % step).rem_euclid(step);
+        x += m * k;
+        m *= step;
+        x = x.rem_euclid(m);
+        if m > i64::MAX as i128 {
+            return None;
+        }
+    }
+    Some((x as i64, m as i64))
+}
+
+/// Returns the first step at which every cycle is at a hit, given each cycle as (offset, period) meaning that it hits
+/// at offset, offset + period, offset + 2 * period, ...
+///
+/// Unlike a plain CRT solution, the result is never earlier than the largest offset. Returns None if the cycles never
+/// align.
+pub fn align_cycles(cycles: &[(i64, i64)]) -> Option<i64> {
+    let (x, m) = crt(cycles)?;
+    let earliest = cycles.iter().map(|&(offset, _)| offset).max().unwrap_or(0);
+    if x >= earliest {
+        return Some(x);
+    }
+    let k = (earliest - x + m - 1) / m;
+    x.checked_add(k.checked_mul(m)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(gcd_of([12, 18, 27]), 3);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(lcm(-4, 6), Some(12));
+        assert_eq!(lcm(0, 6), Some(0));
+        assert_eq!(lcm_of([]), Some(1));
+        assert_eq!(lcm_of([2, 3, 4, 5]), Some(60));
+    }
+
+    #[test]
+    fn lcm_overflow() {
+        let large = i64::MAX / 2;
+        assert_eq!(lcm(large, large - 1), None);
+        assert_eq!(lcm_of([large, large - 1, 2]), None);
+        assert_eq!(lcm(large, 2), Some(large * 2));
+    }
+
+    #[test]
+    fn extended_gcd_identity() {
+        for (a, b) in [(240, 46), (46, 240), (-240, 46), (7, 0), (0, 7), (17, 5)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b), "gcd of {} and {}", a, b);
+            assert_eq!(a * x + b * y, g, "Bezout coefficients of {} and {}", a, b);
+        }
+    }
+
+    #[test]
+    fn mod_inverses() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(10, 17), Some(12));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn chinese_remainders() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+        // The moduli share a factor of 2 but the congruences agree
+        assert_eq!(crt(&[(3, 4), (1, 6)]), Some((7, 12)));
+        // x ≡ 0 (mod 4) and x ≡ 1 (mod 6) disagree about x mod 2
+        assert_eq!(crt(&[(0, 4), (1, 6)]), None);
+        assert_eq!(crt(&[(-1, 5)]), Some((4, 5)));
+        assert_eq!(crt(&[(1, 0)]), None);
+        assert_eq!(crt(&[(0, i64::MAX / 2), (0, i64::MAX / 2 - 1)]), None);
+    }
+
+    #[test]
+    fn aligned_cycles() {
+        assert_eq!(align_cycles(&[(2, 3), (3, 5)]), Some(8));
+        // The CRT solution 2 is before the first hit of the second cycle, so the next solution is used
+        assert_eq!(align_cycles(&[(2, 3), (17, 5)]), Some(17));
+        assert_eq!(align_cycles(&[(20, 20), (20, 30)]), Some(20));
+        assert_eq!(align_cycles(&[(0, 4), (1, 6)]), None);
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Cache statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+/// A cache for memoizing a recursive function by key. The function is given the memo so that its recursive calls go
+/// through the same cache.
+#[derive(Debug)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    limit: Option<usize>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    /// Creates an unbounded memo
+    pub fn new() -> Self {
+        Self {
+            cache: HashMap::new(),
+            limit: None,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Creates a memo that holds at most `limit` entries. Once it is full, new results are computed but not cached.
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            ..Self::new()
+        }
+    }
+
+    /// Returns the cached value for the key, or computes it with f and caches it
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+        self.misses += 1;
+        let value = f(self);
+        if self.limit.is_none_or(|limit| self.cache.len() < limit) {
+            self.cache.insert(key, value.clone());
+        }
+        value
+    }
+
+    /// Returns the cached value for the key, if any, without affecting the statistics
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// Removes all cached values. The statistics are kept.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Returns the hit and miss counts and the number of cached entries
+    pub fn stats(&self) -> Stats {
+        Stats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.cache.len(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
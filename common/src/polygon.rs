@@ -0,0 +1,133 @@
+use crate::math;
+
+/// Where a point lies relative to a polygon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Outside,
+    Boundary,
+}
+
+/// Returns the edges of a closed polygon as pairs of vertices, including the edge from the last vertex to the first
+fn edges(vertices: &[(i64, i64)]) -> impl Iterator<Item = (&(i64, i64), &(i64, i64))> {
+    vertices.iter().zip(vertices.iter().cycle().skip(1)).take(vertices.len())
+}
+
+/// Returns twice the area of a polygon using the Shoelace Formula. Twice the area is always an integer for a polygon
+/// with integer vertices.
+pub fn double_area(vertices: &[(i64, i64)]) -> i64 {
+    let a: i64 = edges(vertices).map(|(v0, v1)| (v0.0 * v1.1) - (v1.0 * v0.1)).sum();
+    a.abs()
+}
+
+/// Returns the area of a polygon using the Shoelace Formula. The area is rounded down if it is not an integer.
+pub fn area(vertices: &[(i64, i64)]) -> i64 {
+    double_area(vertices) / 2
+}
+
+/// Returns the number of integer points on the boundary of a polygon
+pub fn boundary_points(vertices: &[(i64, i64)]) -> i64 {
+    edges(vertices).map(|(v0, v1)| math::gcd(v1.0 - v0.0, v1.1 - v0.1)).sum()
+}
+
+/// Returns the number of integer points strictly inside a polygon using Pick's theorem: A = I + B / 2 - 1
+pub fn interior_points(vertices: &[(i64, i64)]) -> i64 {
+    (double_area(vertices) - boundary_points(vertices) + 2) / 2
+}
+
+/// Returns where a point lies relative to a polygon.
+///
+/// A ray is cast in the +x direction and the crossings are counted. Edges are treated as half-open in y so that a ray
+/// passing through a vertex or along a horizontal edge is counted correctly, and all arithmetic is exact.
+pub fn locate(vertices: &[(i64, i64)], p: (i64, i64)) -> Location {
+    let mut inside = false;
+    for (a, b) in edges(vertices) {
+        let cross = (b.0 - a.0) as i128 * (p.1 - a.1) as i128 - (p.0 - a.0) as i128 * (b.1 - a.1) as i128;
+        let within_x = a.0.min(b.0) <= p.0 && p.0 <= a.0.max(b.0);
+        let within_y = a.1.min(b.1) <= p.1 && p.1 <= a.1.max(b.1);
+        if cross == 0 && within_x && within_y {
+            return Location::Boundary;
+        }
+
+        // The edge crosses the ray if it straddles the ray's y and the point is to the left of the edge
+        if (a.1 > p.1) != (b.1 > p.1) && (cross > 0) == (b.1 > a.1) {
+            inside = !inside;
+        }
+    }
+    if inside {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+/// Returns true if a point is strictly inside a polygon
+pub fn contains(vertices: &[(i64, i64)], p: (i64, i64)) -> bool {
+    locate(vertices, p) == Location::Inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SQUARE: [(i64, i64); 4] = [(0, 0), (4, 0), (4, 4), (0, 4)];
+    // An L with the square from (2, 2) to (4, 4) cut out, so (2, 2) is a reflex vertex
+    const L_SHAPE: [(i64, i64); 6] = [(0, 0), (4, 0), (4, 2), (2, 2), (2, 4), (0, 4)];
+
+    #[test]
+    fn areas() {
+        assert_eq!(area(&SQUARE), 16);
+        assert_eq!(area(&L_SHAPE), 12);
+        let mut clockwise = L_SHAPE;
+        clockwise.reverse();
+        assert_eq!(area(&clockwise), 12);
+        assert_eq!(double_area(&[(0, 0), (3, 0), (0, 1)]), 3);
+        assert_eq!(area(&[(0, 0), (3, 0), (0, 1)]), 1);
+    }
+
+    #[test]
+    fn lattice_points() {
+        assert_eq!(boundary_points(&SQUARE), 16);
+        assert_eq!(interior_points(&SQUARE), 9);
+        assert_eq!(boundary_points(&L_SHAPE), 16);
+        assert_eq!(interior_points(&L_SHAPE), 5);
+        // A diagonal edge only passes through the lattice points at multiples of the gcd of its extents
+        assert_eq!(boundary_points(&[(0, 0), (6, 0), (0, 4)]), 6 + 2 + 4);
+    }
+
+    #[test]
+    fn pick_agrees_with_locate() {
+        for vertices in [&SQUARE[..], &L_SHAPE[..], &[(0, 0), (6, 0), (0, 4)]] {
+            let (mut inside, mut boundary) = (0, 0);
+            for x in -1..=7 {
+                for y in -1..=7 {
+                    match locate(vertices, (x, y)) {
+                        Location::Inside => inside += 1,
+                        Location::Boundary => boundary += 1,
+                        Location::Outside => {}
+                    }
+                }
+            }
+            assert_eq!(inside, interior_points(vertices), "interior of {:?}", vertices);
+            assert_eq!(boundary, boundary_points(vertices), "boundary of {:?}", vertices);
+        }
+    }
+
+    #[test]
+    fn locations() {
+        assert_eq!(locate(&L_SHAPE, (1, 1)), Location::Inside);
+        assert_eq!(locate(&L_SHAPE, (1, 3)), Location::Inside);
+        // The ray from (1, 2) runs along the edge from (2, 2) to (4, 2)
+        assert_eq!(locate(&L_SHAPE, (1, 2)), Location::Inside);
+        assert_eq!(locate(&L_SHAPE, (3, 3)), Location::Outside);
+        assert_eq!(locate(&L_SHAPE, (5, 2)), Location::Outside);
+        assert_eq!(locate(&L_SHAPE, (-1, 2)), Location::Outside);
+        assert_eq!(locate(&L_SHAPE, (-1, 4)), Location::Outside);
+        assert_eq!(locate(&L_SHAPE, (3, 2)), Location::Boundary);
+        assert_eq!(locate(&L_SHAPE, (4, 1)), Location::Boundary);
+        assert_eq!(locate(&L_SHAPE, (2, 2)), Location::Boundary);
+        assert_eq!(locate(&L_SHAPE, (0, 0)), Location::Boundary);
+        assert!(contains(&L_SHAPE, (1, 1)));
+        assert!(!contains(&L_SHAPE, (2, 2)));
+    }
+}
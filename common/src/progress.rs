@@ -0,0 +1,207 @@
+use crate::{args, info};
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+const BAR_INTERVAL: Duration = Duration::from_millis(100);
+const LOG_INTERVAL: Duration = Duration::from_secs(5);
+const BAR_WIDTH: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Bar,
+    Log,
+    Silent,
+}
+
+/// Reports the progress of a long-running loop.
+///
+/// A progress bar is drawn on stderr if it is a terminal. Otherwise, a line is logged at the Info level every few
+/// seconds. Nothing is reported if --json is given so that machine-readable output is not disturbed.
+#[derive(Debug)]
+pub struct Progress {
+    label: String,
+    total: Option<u64>,
+    count: u64,
+    start: Instant,
+    last_report: Instant,
+    mode: Mode,
+    reported: bool,
+    finished: bool,
+}
+
+impl Progress {
+    /// Creates a progress report for a loop with the given number of iterations, or an unknown number if None
+    pub fn new(label: &str, total: Option<u64>) -> Self {
+        let mode = if args::flag("--json") {
+            Mode::Silent
+        } else if io::stderr().is_terminal() {
+            Mode::Bar
+        } else {
+            Mode::Log
+        };
+        Self::with_mode(label, total, mode)
+    }
+
+    fn with_mode(label: &str, total: Option<u64>, mode: Mode) -> Self {
+        let now = Instant::now();
+        Self {
+            label: label.to_string(),
+            total,
+            count: 0,
+            start: now,
+            last_report: now,
+            mode,
+            reported: false,
+            finished: false,
+        }
+    }
+
+    /// Records one iteration
+    pub fn tick(&mut self) {
+        self.inc(1);
+    }
+
+    /// Records n iterations
+    pub fn inc(&mut self, n: u64) {
+        self.count += n;
+        let interval = match self.mode {
+            Mode::Bar => BAR_INTERVAL,
+            Mode::Log => LOG_INTERVAL,
+            Mode::Silent => return,
+        };
+        if self.last_report.elapsed() >= interval {
+            self.last_report = Instant::now();
+            self.reported = true;
+            self.report();
+        }
+    }
+
+    /// Returns the number of iterations recorded so far
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Reports the final state and stops reporting. This is done automatically when the progress is dropped. Nothing
+    /// is reported if the loop finished before the first report was due.
+    pub fn finish(&mut self) {
+        if self.finished || !self.reported {
+            self.finished = true;
+            return;
+        }
+        self.finished = true;
+        match self.mode {
+            Mode::Bar => {
+                self.report();
+                eprintln!();
+            }
+            Mode::Log => self.report(),
+            Mode::Silent => {}
+        }
+    }
+
+    fn report(&self) {
+        let Some(text) = self.report_text() else {
+            return;
+        };
+        match self.mode {
+            Mode::Bar => {
+                eprint!("\r{}\x1b[K", text);
+                io::stderr().flush().ok();
+            }
+            Mode::Log => info!("{}", text),
+            Mode::Silent => {}
+        }
+    }
+
+    // Returns the text of a report, or None if nothing is reported in this mode
+    fn report_text(&self) -> Option<String> {
+        if self.mode == Mode::Silent {
+            return None;
+        }
+        let elapsed = self.start.elapsed().as_secs_f64();
+        let rate = if elapsed > 0.0 { self.count as f64 / elapsed } else { 0.0 };
+        let mut status = match self.total {
+            Some(total) if total > 0 => {
+                let fraction = (self.count as f64 / total as f64).min(1.0);
+                let filled = (fraction * BAR_WIDTH as f64).round() as usize;
+                let mut status = format!("{}/{} ({:.0}%)", self.count, total, fraction * 100.0);
+                if self.mode == Mode::Bar {
+                    status = format!("[{}{}] {}", "#".repeat(filled), ".".repeat(BAR_WIDTH - filled), status);
+                }
+                if rate > 0.0 && self.count < total {
+                    status.push_str(&format!(", ETA {}", format_duration((total - self.count) as f64 / rate)));
+                }
+                status
+            }
+            _ => format!("{}", self.count),
+        };
+        status.push_str(&format!(", {:.1}/s", rate));
+        Some(format!("{}: {}", self.label, status))
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+fn format_duration(seconds: f64) -> String {
+    let seconds = seconds.round() as u64;
+    if seconds >= 3600 {
+        format!("{}h{:02}m", seconds / 3600, seconds / 60 % 60)
+    } else if seconds >= 60 {
+        format!("{}m{:02}s", seconds / 60, seconds % 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Returns a progress report whose first report is already due
+    fn due(mode: Mode) -> Progress {
+        let mut progress = Progress::with_mode("Test", Some(10), mode);
+        progress.last_report -= LOG_INTERVAL;
+        progress
+    }
+
+    #[test]
+    fn silent_reports_nothing() {
+        let mut progress = due(Mode::Silent);
+        progress.inc(5);
+        assert!(!progress.reported);
+        assert_eq!(progress.report_text(), None);
+        progress.finish();
+        assert!(!progress.reported);
+        assert_eq!(progress.count(), 5);
+    }
+
+    #[test]
+    fn log_reports_when_due() {
+        let mut progress = due(Mode::Log);
+        progress.inc(5);
+        assert!(progress.reported);
+        let text = progress.report_text().unwrap();
+        assert!(text.starts_with("Test: 5/10 (50%)"), "{}", text);
+        assert!(!text.contains('['), "{}", text);
+    }
+
+    #[test]
+    fn bar_shows_the_fraction_done() {
+        let mut progress = Progress::with_mode("Test", Some(10), Mode::Bar);
+        progress.inc(5);
+        assert!(!progress.reported);
+        let text = progress.report_text().unwrap();
+        assert!(text.starts_with(&format!("Test: [{}{}] 5/10", "#".repeat(15), ".".repeat(15))), "{}", text);
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(format_duration(42.4), "42s");
+        assert_eq!(format_duration(61.0), "1m01s");
+        assert_eq!(format_duration(3723.0), "1h02m");
+    }
+}
@@ -0,0 +1,106 @@
+use crate::args;
+use std::collections::HashSet;
+use std::io::{self, IsTerminal};
+
+/// Colors available for overlays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Color {
+    pub(crate) fn rgb(self) -> [u8; 3] {
+        match self {
+            Color::Red => [220, 50, 47],
+            Color::Green => [133, 153, 0],
+            Color::Yellow => [238, 200, 0],
+            Color::Blue => [38, 139, 210],
+            Color::Magenta => [211, 54, 130],
+            Color::Cyan => [42, 161, 152],
+        }
+    }
+
+    fn ansi_code(self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+        }
+    }
+}
+
+/// A set of cells drawn over a grid, such as a path, the visited cells, or highlighted coordinates.
+///
+/// In color, the cells are drawn in the overlay's color and keep their character unless the overlay has a glyph. In
+/// plain text, the cells are drawn with the overlay's glyph, which is '#' by default.
+#[derive(Debug, Clone)]
+pub struct Overlay {
+    pub(crate) cells: HashSet<(usize, usize)>,
+    pub(crate) color: Color,
+    glyph: Option<char>,
+}
+
+impl Overlay {
+    /// Creates an overlay of the given (x, y) cells
+    pub fn new(cells: impl IntoIterator<Item = (usize, usize)>, color: Color) -> Self {
+        Self {
+            cells: cells.into_iter().collect(),
+            color,
+            glyph: None,
+        }
+    }
+
+    /// Creates an overlay of the cells in a grid that satisfy a predicate
+    pub fn from_grid<T>(grid: &[Vec<T>], predicate: impl Fn(&T) -> bool, color: Color) -> Self {
+        let cells = grid.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, cell)| predicate(cell))
+                .map(move |(x, _)| (x, y))
+        });
+        Self::new(cells, color)
+    }
+
+    /// Draws the overlay's cells with the given character
+    pub fn with_glyph(mut self, glyph: char) -> Self {
+        self.glyph = Some(glyph);
+        self
+    }
+}
+
+/// Returns true if visualization was requested with --visualize
+pub fn enabled() -> bool {
+    args::flag("--visualize")
+}
+
+/// Renders a grid and its overlays as text. Later overlays are drawn over earlier ones.
+pub fn to_string(grid: &[Vec<char>], overlays: &[Overlay], color: bool) -> String {
+    let mut output = String::new();
+    for (y, row) in grid.iter().enumerate() {
+        for (x, &cell) in row.iter().enumerate() {
+            match overlays.iter().rev().find(|overlay| overlay.cells.contains(&(x, y))) {
+                Some(overlay) if color => {
+                    let c = overlay.glyph.unwrap_or(cell);
+                    output.push_str(&format!("\x1b[{}m{}\x1b[0m", overlay.color.ansi_code(), c));
+                }
+                Some(overlay) => output.push(overlay.glyph.unwrap_or('#')),
+                None => output.push(cell),
+            }
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Prints a grid and its overlays, in color if stdout is a terminal
+pub fn print(grid: &[Vec<char>], overlays: &[Overlay]) {
+    println!("{}", to_string(grid, overlays, io::stdout().is_terminal()));
+}
@@ -0,0 +1,156 @@
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// How a shape is filled and outlined. Stroke widths are in pixels regardless of the drawing's scale.
+#[derive(Debug, Clone, Default)]
+pub struct Style {
+    fill: Option<String>,
+    fill_opacity: Option<f64>,
+    stroke: Option<String>,
+    stroke_width: f64,
+}
+
+impl Style {
+    /// A style with no fill and no outline
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fills the shape with a color
+    pub fn fill(mut self, color: &str) -> Self {
+        self.fill = Some(color.to_string());
+        self
+    }
+
+    /// Fills the shape with a translucent color
+    pub fn fill_translucent(mut self, color: &str, opacity: f64) -> Self {
+        self.fill = Some(color.to_string());
+        self.fill_opacity = Some(opacity);
+        self
+    }
+
+    /// Outlines the shape with a color
+    pub fn stroke(mut self, color: &str, width: f64) -> Self {
+        self.stroke = Some(color.to_string());
+        self.stroke_width = width;
+        self
+    }
+
+    fn attributes(&self) -> String {
+        let mut attributes = format!(r#"fill="{}""#, self.fill.as_deref().unwrap_or("none"));
+        if let Some(opacity) = self.fill_opacity {
+            write!(attributes, r#" fill-opacity="{}""#, opacity).unwrap();
+        }
+        if let Some(stroke) = &self.stroke {
+            write!(
+                attributes,
+                r#" stroke="{}" stroke-width="{}" vector-effect="non-scaling-stroke""#,
+                stroke, self.stroke_width
+            )
+            .unwrap();
+        }
+        attributes
+    }
+}
+
+/// A drawing that is written as an SVG file. Coordinates are in the drawing's own units and the drawing is scaled to
+/// fit the image.
+#[derive(Debug)]
+pub struct Svg {
+    min: (f64, f64),
+    max: (f64, f64),
+    elements: Vec<String>,
+}
+
+impl Svg {
+    /// Creates a drawing covering the area from min to max
+    pub fn new(min: (f64, f64), max: (f64, f64)) -> Self {
+        Self {
+            min,
+            max,
+            elements: Vec::new(),
+        }
+    }
+
+    /// Creates a drawing covering all the points with a margin around them
+    pub fn fitting(points: impl IntoIterator<Item = (f64, f64)>, margin: f64) -> Self {
+        let (min, max) = points.into_iter().fold(
+            ((f64::INFINITY, f64::INFINITY), (f64::NEG_INFINITY, f64::NEG_INFINITY)),
+            |(min, max), p| ((min.0.min(p.0), min.1.min(p.1)), (max.0.max(p.0), max.1.max(p.1))),
+        );
+        Self::new((min.0 - margin, min.1 - margin), (max.0 + margin, max.1 + margin))
+    }
+
+    /// Returns the size of the area covered by the drawing
+    pub fn size(&self) -> (f64, f64) {
+        (self.max.0 - self.min.0, self.max.1 - self.min.1)
+    }
+
+    /// Draws a closed polygon
+    pub fn polygon(&mut self, points: &[(f64, f64)], style: &Style) {
+        let points: Vec<String> = points.iter().map(|p| format!("{},{}", p.0, p.1)).collect();
+        self.elements.push(format!(r#"<polygon points="{}" {}/>"#, points.join(" "), style.attributes()));
+    }
+
+    /// Draws a line segment
+    pub fn line(&mut self, from: (f64, f64), to: (f64, f64), style: &Style) {
+        self.elements.push(format!(
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" {}/>"#,
+            from.0,
+            from.1,
+            to.0,
+            to.1,
+            style.attributes()
+        ));
+    }
+
+    /// Draws a rectangle
+    pub fn rect(&mut self, min: (f64, f64), max: (f64, f64), style: &Style) {
+        self.elements.push(format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" {}/>"#,
+            min.0,
+            min.1,
+            max.0 - min.0,
+            max.1 - min.1,
+            style.attributes()
+        ));
+    }
+
+    /// Draws a circle
+    pub fn circle(&mut self, center: (f64, f64), radius: f64, style: &Style) {
+        self.elements.push(format!(
+            r#"<circle cx="{}" cy="{}" r="{}" {}/>"#,
+            center.0,
+            center.1,
+            radius,
+            style.attributes()
+        ));
+    }
+
+    /// Returns the drawing as an SVG document
+    pub fn to_svg(&self) -> String {
+        let (width, height) = self.size();
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="800" height="{}" viewBox="{} {} {} {}">"#,
+            (800.0 * height / width).round(),
+            self.min.0,
+            self.min.1,
+            width,
+            height
+        );
+        svg.push('\n');
+        for element in &self.elements {
+            svg.push_str(element);
+            svg.push('\n');
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Writes the drawing to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_svg())
+    }
+}
@@ -0,0 +1,137 @@
+use crate::{args, load};
+use std::fmt;
+use std::process;
+
+/// A structural problem found in an input file. Line and column numbers start at 1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub line: usize,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl Problem {
+    /// Creates a problem for a whole line, given the line's index
+    pub fn line(index: usize, message: impl Into<String>) -> Self {
+        Self {
+            line: index + 1,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    /// Creates a problem at a character in a line, given the indexes of the line and character
+    pub fn at(index: usize, column: usize, message: impl Into<String>) -> Self {
+        Self {
+            line: index + 1,
+            column: Some(column + 1),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "line {}, column {}: {}", self.line, column, self.message),
+            None => write!(f, "line {}: {}", self.line, self.message),
+        }
+    }
+}
+
+/// Validates the input file before solving.
+///
+/// With --check, the problems are reported and the process exits with status 0 if there are none and 1 otherwise.
+/// Without --check, the process only exits, with status 1, if there are problems.
+pub fn check(validate: impl Fn(&[String]) -> Vec<Problem>) {
+    let lines = match load::lines() {
+        Ok(lines) => lines,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+    let problems = validate(&lines);
+    if args::flag("--check") {
+        for problem in &problems {
+            println!("{}", problem);
+        }
+        if problems.is_empty() {
+            println!("OK");
+            process::exit(0);
+        }
+        process::exit(1);
+    }
+    if !problems.is_empty() {
+        for problem in &problems {
+            eprintln!("{}", problem);
+        }
+        eprintln!("The input is not valid");
+        process::exit(1);
+    }
+}
+
+/// Returns the problems found by checking each line with a function that returns an error message for a bad line.
+/// Blank lines are checked too.
+pub fn each_line(lines: &[String], check: impl Fn(&str) -> Result<(), String>) -> Vec<Problem> {
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| check(line).err().map(|message| Problem::line(i, message)))
+        .collect()
+}
+
+/// Returns the problems in a grid: no rows, rows of different lengths, and characters not in the alphabet
+pub fn grid(lines: &[String], alphabet: &str) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    if lines.is_empty() || lines[0].is_empty() {
+        problems.push(Problem::line(0, "the grid is empty"));
+        return problems;
+    }
+    let width = lines[0].chars().count();
+    for (i, line) in lines.iter().enumerate() {
+        let length = line.chars().count();
+        if length != width {
+            problems.push(Problem::line(i, format!("expected {} characters, found {}", width, length)));
+        }
+        for (j, c) in line.chars().enumerate() {
+            if !alphabet.contains(c) {
+                problems.push(Problem::at(i, j, format!("unexpected character {:?}", c)));
+            }
+        }
+    }
+    problems
+}
+
+/// Returns a problem if the character does not appear exactly once in the lines
+pub fn exactly_one(lines: &[String], c: char) -> Vec<Problem> {
+    let positions: Vec<(usize, usize)> = lines
+        .iter()
+        .enumerate()
+        .flat_map(|(i, line)| line.chars().enumerate().filter(|&(_, x)| x == c).map(move |(j, _)| (i, j)))
+        .collect();
+    match positions.len() {
+        0 => vec![Problem::line(0, format!("missing {:?}", c))],
+        1 => Vec::new(),
+        _ => positions[1..]
+            .iter()
+            .map(|&(i, j)| Problem::at(i, j, format!("more than one {:?}", c)))
+            .collect(),
+    }
+}
+
+/// Parses a list of numbers separated by whitespace or the given separator, returning an error message if any are bad
+pub fn numbers(s: &str, separator: char) -> Result<Vec<i64>, String> {
+    s.split(|c: char| c == separator || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.parse().map_err(|_| format!("{:?} is not a number", token)))
+        .collect()
+}
+
+/// Returns the parsed value, or reports the problem and exits with status 1
+pub fn or_exit<T>(result: Result<T, Problem>) -> T {
+    result.unwrap_or_else(|problem| {
+        eprintln!("{}", problem);
+        process::exit(1);
+    })
+}
@@ -0,0 +1,20 @@
+use crate::generate::Rng;
+
+/// Checks a property of a day's solver on random inputs, for use in the day's tests. The property is usually that the
+/// fast solver agrees with a slow but obviously correct one. It returns an error message describing the input if it
+/// does not hold.
+///
+/// Each of the cases has its own seed counting up from 0 and a random size up to max_size. A failing case is retried
+/// at smaller sizes with the same seed, and the smallest failure is reported in the panic with its seed and size.
+pub fn property(cases: u64, max_size: usize, check: impl Fn(&mut Rng, usize) -> Result<(), String>) {
+    let max_size = max_size.max(1);
+    for seed in 0..cases {
+        let size = 1 + (Rng::new(seed).next_u64() % max_size as u64) as usize;
+        if let Err(message) = check(&mut Rng::new(seed), size) {
+            let (size, message) = (1..size)
+                .find_map(|smaller| check(&mut Rng::new(seed), smaller).err().map(|m| (smaller, m)))
+                .unwrap_or((size, message));
+            panic!("Failed with seed {} and size {}: {}", seed, size, message);
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use aho_corasick::{AhoCorasick, Match};
+use common::validate::Problem;
+use std::cmp::Reverse;
+
+/// The English names of the digits, by value
+pub const DIGIT_NAMES: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+/// Words recognized as digits, including the numeric digits themselves, all matched in a single pass over a line
+pub struct Vocabulary {
+    words: Vec<String>,
+    values: Vec<u32>,
+    matcher: AhoCorasick,
+}
+
+impl Vocabulary {
+    // Creates a vocabulary of the numeric digits and the given words
+    fn new(words: &[(String, u32)]) -> Result<Self, String> {
+        let (words, values): (Vec<String>, Vec<u32>) =
+            (0..10).map(|d| (d.to_string(), d)).chain(words.iter().cloned()).unzip();
+        let matcher = AhoCorasick::new(&words).map_err(|e| e.to_string())?;
+        Ok(Self { words, values, matcher })
+    }
+
+    /// Returns the English names of the digits in part 2, and no names in part 1
+    pub fn english() -> Self {
+        let names: Vec<(String, u32)> = if cfg!(feature = "part2") {
+            DIGIT_NAMES.iter().enumerate().map(|(value, name)| (name.to_string(), value as u32)).collect()
+        } else {
+            Vec::new()
+        };
+        Self::new(&names).expect("the digit names are few and short")
+    }
+
+    /// Parses lines of "<word> <value>", where the value is a digit. Blank lines and lines starting with '#' are
+    /// ignored.
+    pub fn parse(lines: &[String]) -> Result<Self, Problem> {
+        let mut words = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (word, value) =
+                line.rsplit_once(char::is_whitespace).ok_or_else(|| Problem::line(i, "expected \"<word> <value>\""))?;
+            let value = value.parse().ok().filter(|&value| value < 10);
+            let value = value.ok_or_else(|| Problem::line(i, "the value must be a digit"))?;
+            words.push((word.trim().to_string(), value));
+        }
+        Self::new(&words).map_err(|message| Problem::line(lines.len().saturating_sub(1), message))
+    }
+
+    /// Returns true if the character appears in any of the words
+    pub fn contains(&self, c: char) -> bool {
+        self.words.iter().any(|word| word.contains(c))
+    }
+
+    /// Returns the values of the first and last words in a string. Overlapping words, as in "twone", are all found,
+    /// and of the words starting at the same place, the longest is used.
+    pub fn first_and_last(&self, s: &str) -> Option<(u32, u32)> {
+        let matches: Vec<Match> = self.matcher.find_overlapping_iter(s).collect();
+        let first = matches.iter().min_by_key(|m| (m.start(), Reverse(m.end())))?;
+        let last = matches.iter().max_by_key(|m| (m.start(), m.end()))?;
+        Some((self.values[first.pattern().as_usize()], self.values[last.pattern().as_usize()]))
+    }
+}
+
+/// Returns the number formed by the first and last digits in a line, or an error if it has no digits
+pub fn calibration_value(vocabulary: &Vocabulary, line: &str) -> Result<u32, String> {
+    let (first, last) = vocabulary.first_and_last(line).ok_or("no digit")?;
+    Ok(first * 10 + last)
+}
@@ -1,101 +1,83 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{args, load, trace};
+use day01::{calibration_value, Vocabulary, DIGIT_NAMES};
+use std::fs;
+use std::process;
 
-const DIGIT_NAMES: [&str; 10] = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
-
-// Returns the first digit in a string
-fn first_number_in(s: &str) -> Option<(usize, u32)> {
-    s.char_indices().find_map(|(i, c)| {
-        if c.is_ascii_digit() {
-            Some((i, c.to_digit(10)?))
-        } else {
-            None
-        }
-    })
-}
-
-// Returns the last digit in a string
-fn last_number_in(s: &str) -> Option<(usize, u32)> {
-    s.char_indices().rev().find_map(|(i, c)| {
-        if c.is_ascii_digit() {
-            Some((i, c.to_digit(10)?))
-        } else {
-            None
-        }
-    })
-}
-
-// Returns the first digit by name in a string
-fn first_name_in(s: &str) -> Option<(usize, usize)> {
-    let mut first_value: Option<usize> = None;
-    let mut first_pos: Option<usize> = None;
-    for (value, &digit_name) in DIGIT_NAMES.iter().enumerate() {
-        if let Some(pos) = s.find(digit_name) {
-            if first_pos.is_none() || pos < first_pos.unwrap() {
-                first_pos = Some(pos);
-                first_value = Some(value);
-            }
-        }
-    }
-    if first_pos.is_none() {
-        return None;
-    }
-    return Some((first_pos.unwrap(), first_value.unwrap()));
-}
-
-// Returns the last digit by name in a string
-fn last_name_in(s: &str) -> Option<(usize, usize)> {
-    let mut last_value: Option<usize> = None;
-    let mut last_pos: Option<usize> = None;
-    for (value, &digit_name) in DIGIT_NAMES.iter().enumerate().rev() {
-        if let Some(pos) = s.rfind(digit_name) {
-            if last_pos.is_none() || pos > last_pos.unwrap() {
-                last_pos = Some(pos);
-                last_value = Some(value);
-            }
+// Returns the vocabulary in the file given with --vocabulary, or the English names of the digits
+fn vocabulary_from_args() -> Vocabulary {
+    match args::value("--vocabulary") {
+        Some(path) => {
+            let vocabulary = fs::read_to_string(&path)
+                .map_err(|e| format!("Could not read \"{}\": {}", path, e))
+                .and_then(|text| {
+                    Vocabulary::parse(&load::parse_lines(&text))
+                        .map_err(|problem| format!("Invalid vocabulary \"{}\": {}", path, problem))
+                });
+            vocabulary.unwrap_or_else(|message| {
+                eprintln!("{}", message);
+                process::exit(1);
+            })
         }
+        None => Vocabulary::english(),
     }
-    if last_pos.is_none() {
-        return None;
-    }
-    return Some((last_pos.unwrap(), last_value.unwrap()));
 }
 
-// Returns the first digit
-fn first_value((a1, a2): (usize, u32), (b1, b2): (usize, usize)) -> u32 {
-    if a1 < b1 {
-        a2
-    } else {
-        b2 as u32
-    }
-}
-// Returns the last digit
-fn last_value((a1, a2): (usize, u32), (b1, b2): (usize, usize)) -> u32 {
-    if a1 > b1 {
-        a2
-    } else {
-        b2 as u32
-    }
-}
 fn main() {
+    generate::run(1000, generate);
+    validate::check(validate);
     println!("Day 1, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
     let lines = load::lines().unwrap();
+    let vocabulary = vocabulary_from_args();
 
     let mut sum = 0;
-    for line in lines {
-        let first_number = first_number_in(&line).unwrap();
-        let (_, mut first_digit) = first_number;
-        let first_name = first_name_in(&line);
-        if first_name.is_some() {
-            first_digit = first_value(first_number, first_name.unwrap());
+    for (i, line) in lines.iter().enumerate() {
+        let value = calibration_value(&vocabulary, line).map_err(|message| Problem::line(i, message));
+        let value = validate::or_exit(value);
+        trace!("{}: {}", line, value);
+        sum += value;
+    }
+
+    println!("Sum: {}", sum);
+}
+
+// Returns the problems in the input: each line is lowercase letters and digits, and contains at least one digit (or
+// a word for one). Characters in the words of the vocabulary are allowed too.
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let vocabulary = vocabulary_from_args();
+    let mut problems = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let unexpected = |c: char| !c.is_ascii_lowercase() && !c.is_ascii_digit() && !vocabulary.contains(c);
+        if let Some((j, c)) = line.chars().enumerate().find(|&(_, c)| unexpected(c)) {
+            problems.push(Problem::at(i, j, format!("unexpected character {:?}", c)));
         }
-        let last_number = last_number_in(&line).unwrap();
-        let (_, mut last_digit) = last_number;
-        let last_name = last_name_in(&line);
-        if last_name.is_some() {
-            last_digit = last_value(last_number, last_name.unwrap());
+        if let Err(message) = calibration_value(&vocabulary, line) {
+            problems.push(Problem::line(i, message));
         }
-        sum += first_digit * 10 + last_digit;
     }
+    problems
+}
 
-    println!("Sum: {}", sum);
+// Returns lines of random letters with digits, and digit names in part 2, mixed in. The size is the number of lines.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            let length = rng.below(12);
+            let mut line = rng.string(length, "abcdefghijklmnopqrstuvwxyz");
+            // In part 2, some lines only have the names of digits
+            let digits = if cfg!(feature = "part2") { rng.below(4) } else { rng.between(1, 3) as usize };
+            for _ in 0..digits {
+                let at = rng.below(line.len() + 1);
+                line.insert(at, char::from_digit(rng.between(1, 9) as u32, 10).unwrap());
+            }
+            if cfg!(feature = "part2") {
+                for _ in 0..if digits == 0 { rng.between(1, 3) as usize } else { rng.below(3) } {
+                    let at = rng.below(line.len() + 1);
+                    line.insert_str(at, DIGIT_NAMES[rng.between(1, 9) as usize]);
+                }
+            }
+            line
+        })
+        .collect()
 }
@@ -0,0 +1,63 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::Problem;
+use std::collections::BTreeMap;
+
+/// Parses the games, one per line
+pub fn parse_games(lines: &[String]) -> Result<Vec<Game>, Problem> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| Game::parse(line).map_err(|message| Problem::line(i, message)))
+        .collect()
+}
+
+/// The number of cubes of each color shown in a round. The contents of a bag are counted the same way.
+pub type Round = BTreeMap<String, u32>;
+
+/// A game: its id and the rounds of cubes shown
+#[derive(Debug)]
+pub struct Game {
+    pub id: u32,
+    pub rounds: Vec<Round>,
+}
+
+impl Game {
+    /// Parses "Game <id>: " followed by rounds separated by "; "
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let (game, rounds) = line.split_once(": ").ok_or("missing \": \"")?;
+        let id = game.strip_prefix("Game ").ok_or("expected \"Game <id>\"")?;
+        let id = id.parse().map_err(|_| format!("{:?} is not a game id", id))?;
+        let rounds = rounds.split("; ").map(parse_cubes).collect::<Result<_, _>>()?;
+        Ok(Self { id, rounds })
+    }
+
+    /// Returns true if every round could have been drawn from the bag. There are no cubes of a color not in the bag.
+    pub fn is_possible(&self, bag: &Round) -> bool {
+        self.rounds
+            .iter()
+            .all(|round| round.iter().all(|(color, &count)| count <= bag.get(color).copied().unwrap_or(0)))
+    }
+
+    /// Returns the fewest cubes of each color that the bag could have held
+    pub fn minimal_bag(&self) -> Round {
+        let mut bag = Round::new();
+        for (color, &count) in self.rounds.iter().flatten() {
+            let most = bag.entry(color.clone()).or_insert(0);
+            *most = (*most).max(count);
+        }
+        bag
+    }
+}
+
+/// Parses a list of "<count> <color>" separated by ", ". The counts of a color listed more than once are added.
+pub fn parse_cubes(s: &str) -> Result<Round, String> {
+    let mut round = Round::new();
+    for cubes in s.split(", ") {
+        let (count, color) = cubes.split_once(' ').ok_or(format!("expected \"<count> <color>\", found {:?}", cubes))?;
+        let count: u32 = count.parse().map_err(|_| format!("{:?} is not a count", count))?;
+        let total = round.entry(color.to_string()).or_insert(0);
+        *total = total.checked_add(count).ok_or(format!("too many {} cubes", color))?;
+    }
+    Ok(round)
+}
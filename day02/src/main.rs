@@ -1,110 +1,75 @@
-use common::load;
-use regex::Regex;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{args, debug, load};
+use day02::{parse_cubes, parse_games, Game, Round};
 
 // which games would have been possible if the bag contained only 12 red cubes, 13 green cubes, and 14 blue cubes?
-#[cfg(not(feature = "part2"))]
-const MAX: (i32, i32, i32) = (12, 13, 14);
+const DEFAULT_BAG: &str = "12 red, 13 green, 14 blue";
+
+// Returns the contents of the bag given with --bag, such as "12 red, 13 green, 14 blue"
+fn bag_from_args() -> Round {
+    let bag = args::value("--bag").unwrap_or_else(|| DEFAULT_BAG.to_string());
+    parse_cubes(&bag).unwrap_or_else(|e| panic!("Invalid --bag value: {}", e))
+}
 
 fn main() {
+    generate::run(100, generate);
+    validate::check(validate);
     println!("Day 2, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
 
-    let games = load::lines().unwrap();
-
-    #[cfg(not(feature = "part2"))]
-    part1(&games);
-    #[cfg(feature = "part2")]
-    part2(&games);
-}
+    let lines = load::lines().unwrap();
+    let bag = bag_from_args();
+    let games = validate::or_exit(parse_games(&lines));
 
-#[cfg(not(feature = "part2"))]
-fn part1(games: &[String]) {
     let mut id_sum = 0;
-
-    // Check games
-    for game in games {
-        let mut red = 0;
-        let mut green = 0;
-        let mut blue = 0;
-        let mut failed = false;
-
-        // Parse a game
-        let game_regex = Regex::new(r"Game (\d+):").unwrap();
-        let game_id: i32 = game_regex.captures(game).unwrap()[1].parse().unwrap();
-        let tuple_regex = Regex::new(r"(\d+) (\w+)([,;]?)").unwrap();
-        for cap in tuple_regex.captures_iter(&game) {
-            let number: i32 = cap[1].parse().unwrap();
-            let color: &str = &cap[2];
-            let separator: &str = &cap[3];
-            match color {
-                "red" => red += number,
-                "green" => green += number,
-                "blue" => blue += number,
-                _ => panic!("Unknown color {}", color),
-            }
-            if separator != "," {
-                if red > MAX.0 || green > MAX.1 || blue > MAX.2 {
-                    failed = true;
-                }
-                red = 0;
-                green = 0;
-                blue = 0;
-            }
-        }
-        if !failed {
-            id_sum += game_id;
+    let mut sum_of_powers: u64 = 0;
+    for game in &games {
+        let possible = game.is_possible(&bag);
+        let minimal_bag = game.minimal_bag();
+        // The power of a game is the product of the fewest cubes of each color in the bag. A color the game never shows
+        // has none, so the power is 0.
+        let power: u64 = bag.keys().map(|color| minimal_bag.get(color).copied().unwrap_or(0) as u64).product();
+        debug!(
+            "Game {}: {}, minimal bag {:?}, power {}",
+            game.id,
+            if possible { "possible" } else { "impossible" },
+            minimal_bag,
+            power
+        );
+        if possible {
+            id_sum += game.id;
         }
+        sum_of_powers += power;
     }
 
-    println!("Sum of game ids is {}", id_sum);
+    if cfg!(feature = "part2") {
+        println!("Sum of powers is {}", sum_of_powers);
+    } else {
+        println!("Sum of game ids is {}", id_sum);
+    }
 }
 
-#[cfg(feature = "part2")]
-fn part2(games: &[String]) {
-    let mut sum_of_powers = 0;
-
-    // Check games
-    for game in games {
-        let mut red = 0;
-        let mut green = 0;
-        let mut blue = 0;
-        let mut max_red: Option<i32> = None;
-        let mut max_green: Option<i32> = None;
-        let mut max_blue: Option<i32> = None;
-
-        // Parse a game
-        let tuple_regex = Regex::new(r"(\d+) (\w+)([,;]?)").unwrap();
-        for cap in tuple_regex.captures_iter(&game) {
-            let number: i32 = cap[1].parse().unwrap();
-            let color: &str = &cap[2];
-            let separator: &str = &cap[3];
-            match color {
-                "red" => red += number,
-                "green" => green += number,
-                "blue" => blue += number,
-                _ => panic!("Unknown color {}", color),
-            }
-            if separator != "," {
-                if max_red.is_none() || red > max_red.unwrap() {
-                    max_red = Some(red);
-                }
-                if max_green.is_none() || green > max_green.unwrap() {
-                    max_green = Some(green);
-                }
-                if max_blue.is_none() || blue > max_blue.unwrap() {
-                    max_blue = Some(blue);
-                }
-
-                red = 0;
-                green = 0;
-                blue = 0;
-            }
-        }
-
-        {
-            let power = max_red.unwrap() * max_green.unwrap() * max_blue.unwrap();
-            sum_of_powers += power;
-        }
-    }
+// Returns the problems in the input. Each line is a game. A game may show colors that are not in the bag, which makes
+// it impossible.
+fn validate(lines: &[String]) -> Vec<Problem> {
+    validate::each_line(lines, |line| Game::parse(line).map(|_| ()))
+}
 
-    println!("Sum of powers is {}", sum_of_powers);
+// Returns random games of one to six rounds. The size is the number of games.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    (1..=size)
+        .map(|id| {
+            let rounds: Vec<String> = (0..rng.between(1, 6))
+                .map(|_| {
+                    let mut colors = ["red", "green", "blue"];
+                    rng.shuffle(&mut colors);
+                    let count = rng.between(1, 3) as usize;
+                    let cubes: Vec<String> =
+                        colors[..count].iter().map(|color| format!("{} {}", rng.between(1, 20), color)).collect();
+                    cubes.join(", ")
+                })
+                .collect();
+            format!("Game {}: {}", id, rounds.join("; "))
+        })
+        .collect()
 }
@@ -0,0 +1,104 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::Problem;
+use std::collections::HashMap;
+
+/// A number in the schematic and the columns it spans in its row
+#[derive(Debug)]
+pub struct Number {
+    pub value: u64,
+    pub row: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A symbol in the schematic and its position
+#[derive(Debug)]
+pub struct Symbol {
+    pub c: char,
+    pub row: usize,
+    pub column: usize,
+}
+
+/// The numbers and symbols in the schematic, and which of them are adjacent (including diagonally)
+#[derive(Debug)]
+pub struct Schematic {
+    pub numbers: Vec<Number>,
+    pub symbols: Vec<Symbol>,
+    symbols_by_number: Vec<Vec<usize>>,
+    numbers_by_symbol: Vec<Vec<usize>>,
+}
+
+impl Schematic {
+    /// Extracts the numbers and symbols from the rows of the schematic and indexes their adjacency
+    pub fn parse(lines: &[String]) -> Result<Self, Problem> {
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+        for (row, line) in lines.iter().enumerate() {
+            let mut number: Option<Number> = None;
+            for (column, c) in line.chars().enumerate() {
+                if let Some(digit) = c.to_digit(10) {
+                    let n = number.get_or_insert(Number { value: 0, row, start: column, end: column });
+                    let value = n.value.checked_mul(10).and_then(|value| value.checked_add(digit as u64));
+                    n.value = value.ok_or_else(|| Problem::at(row, n.start, "the number is too large"))?;
+                    n.end = column + 1;
+                } else {
+                    numbers.extend(number.take());
+                    if is_symbol(c) {
+                        symbols.push(Symbol { c, row, column });
+                    }
+                }
+            }
+            numbers.extend(number);
+        }
+
+        // Look for symbols in the cells surrounding each number
+        let symbol_at: HashMap<(usize, usize), usize> =
+            symbols.iter().enumerate().map(|(i, symbol)| ((symbol.row, symbol.column), i)).collect();
+        let mut symbols_by_number = vec![Vec::new(); numbers.len()];
+        let mut numbers_by_symbol = vec![Vec::new(); symbols.len()];
+        for (i, number) in numbers.iter().enumerate() {
+            for row in number.row.saturating_sub(1)..=number.row + 1 {
+                for column in number.start.saturating_sub(1)..=number.end {
+                    if let Some(&j) = symbol_at.get(&(row, column)) {
+                        symbols_by_number[i].push(j);
+                        numbers_by_symbol[j].push(i);
+                    }
+                }
+            }
+        }
+
+        Ok(Self { numbers, symbols, symbols_by_number, numbers_by_symbol })
+    }
+
+    /// Returns the numbers adjacent to any symbol
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers.iter().zip(&self.symbols_by_number).filter(|(_, symbols)| !symbols.is_empty()).map(|(n, _)| n)
+    }
+
+    /// Returns the numbers adjacent to a symbol that is one of the given characters
+    pub fn numbers_adjacent_to(&self, characters: &str) -> Vec<&Number> {
+        self.numbers
+            .iter()
+            .zip(&self.symbols_by_number)
+            .filter(|(_, symbols)| symbols.iter().any(|&j| characters.contains(self.symbols[j].c)))
+            .map(|(n, _)| n)
+            .collect()
+    }
+
+    /// Returns the symbols that are one of the given characters and are adjacent to exactly `count` numbers, along with
+    /// the numbers
+    pub fn symbols_with_adjacent(&self, characters: &str, count: usize) -> Vec<(&Symbol, Vec<&Number>)> {
+        self.symbols
+            .iter()
+            .zip(&self.numbers_by_symbol)
+            .filter(|(symbol, numbers)| characters.contains(symbol.c) && numbers.len() == count)
+            .map(|(symbol, numbers)| (symbol, numbers.iter().map(|&i| &self.numbers[i]).collect()))
+            .collect()
+    }
+}
+
+// Returns true if the character is a symbol
+fn is_symbol(ch: char) -> bool {
+    ch != '.' && !ch.is_ascii_digit()
+}
@@ -1,157 +1,88 @@
-use common::load;
-    #[cfg(feature = "part2")]
-use std::collections::BTreeMap;
-use std::collections::BTreeSet;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{args, debug, load};
+use day03::Schematic;
+
+// What makes a symbol a gear: one of the characters, adjacent to exactly a number of part numbers
+#[derive(Debug)]
+struct Gear {
+    characters: String,
+    parts: usize,
+}
+
+impl Gear {
+    // Returns the gear given with --gear as "<characters>:<parts>", or '*' with two parts by default
+    fn from_args() -> Self {
+        let Some(gear) = args::value("--gear") else {
+            return Self { characters: "*".to_string(), parts: 2 };
+        };
+        let parsed = gear.rsplit_once(':').and_then(|(characters, parts)| Some((characters, parts.parse().ok()?)));
+        match parsed {
+            Some((characters, parts)) if !characters.is_empty() => Self { characters: characters.to_string(), parts },
+            _ => panic!("Invalid --gear value: {}", gear),
+        }
+    }
+}
 
 fn main() {
+    generate::run(140, generate);
+    validate::check(validate);
     println!("Day 3, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
     let lines = load::lines().unwrap();
 
-    // Create a 2D array of characters
-    let mut grid: Vec<Vec<char>> = Vec::new();
-
-    for s in lines {
-        grid.push(s.chars().collect());
-    }
+    let schematic = validate::or_exit(Schematic::parse(&lines));
+    debug!("Numbers: {}, symbols: {}", schematic.numbers.len(), schematic.symbols.len());
 
-    #[cfg(not(feature = "part2"))]
     let mut sum = 0;
-    #[cfg(feature = "part2")]
-    let mut gears: BTreeMap<(usize, usize), Vec<u32>> = BTreeMap::new();
-
-    // Scan the grid for part numbers
-    for y in 0..grid.len() {
-        let mut x = 0;
-        while x < grid[y].len() {
-            if grid[y][x].is_ascii_digit() {
-                #[cfg(not(feature = "part2"))]
-                let (new_x, value, is_part_number, _) = scan_number(&grid, x, y);
-                #[cfg(not(feature = "part2"))]
-                if is_part_number {
-                    sum += value;
-                }
-                #[cfg(feature = "part2")]
-                let (new_x, value, is_part_number, adjacent_gears) = scan_number(&grid, x, y);
-                #[cfg(feature = "part2")]
-                if is_part_number {
-                    for g in adjacent_gears {
-                        gears.entry(g).or_insert(Vec::new()).push(value);
-                    }
-                }
-                x = new_x;
-            }
-            x = x + 1;
-        }
+    for number in schematic.part_numbers() {
+        debug!("Part number {} at ({}, {})", number.value, number.start, number.row);
+        sum += number.value;
     }
 
-    #[cfg(not(feature = "part2"))]
-    println!("Sum: {}", sum);
-
-    #[cfg(feature = "part2")]
-    {
-        let mut gear_ratio_sum: u64 = 0;
-        for g in gears {
-            if g.1.len() == 2 {
-                gear_ratio_sum += g.1[0] as u64 * g.1[1] as u64;
-            }
-        }
+    // The gear ratio is the product of the gear's part numbers
+    let gear = Gear::from_args();
+    debug!("Numbers adjacent to {:?}: {}", gear.characters, schematic.numbers_adjacent_to(&gear.characters).len());
+    let mut gear_ratio_sum: u64 = 0;
+    for (symbol, numbers) in schematic.symbols_with_adjacent(&gear.characters, gear.parts) {
+        let ratio: u64 = numbers.iter().map(|number| number.value).product();
+        debug!("Gear {} at ({}, {}): ratio = {}", symbol.c, symbol.column, symbol.row, ratio);
+        gear_ratio_sum += ratio;
+    }
 
+    if cfg!(feature = "part2") {
         println!("Gear ratio sum: {}", gear_ratio_sum);
+    } else {
+        println!("Sum: {}", sum);
     }
 }
 
-// Returns true if the character is a symbol
-fn is_symbol(ch: char) -> bool {
-    ch != '.' && !ch.is_ascii_digit()
-}
-
-// Returns true if the character is a gear
-fn is_gear(ch: char) -> bool {
-    ch == '*'
-}
-
-// Returns true if the character adjacent to a symbol
-fn is_adjacent_to_symbol(grid: &Vec<Vec<char>>, x: usize, y: usize) -> bool {
-    if x > 0 {
-        if is_symbol(grid[y][x - 1]) {
-            return true;
-        }
-        if y > 0 && is_symbol(grid[y - 1][x - 1]) {
-            return true;
-        }
-        if y < grid.len() - 1 && is_symbol(grid[y + 1][x - 1]) {
-            return true;
-        }
-    }
-
-    if y > 0 && is_symbol(grid[y - 1][x]) {
-        return true;
-    }
-    if y < grid.len() - 1 && is_symbol(grid[y + 1][x]) {
-        return true;
-    }
-
-    if x < grid[y].len() - 1 {
-        if is_symbol(grid[y][x + 1]) {
-            return true;
-        }
-        if y > 0 && is_symbol(grid[y - 1][x + 1]) {
-            return true;
-        }
-        if y < grid.len() - 1 && is_symbol(grid[y + 1][x + 1]) {
-            return true;
-        }
-    }
-
-    false
+// Returns the problems in the input, which is a grid of digits, '.', and symbols
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let alphabet: String = ('!'..='~').collect();
+    validate::grid(lines, &alphabet)
 }
 
-// Returns true if the character adjacent to a symbol
-fn find_adjacent_gears(grid: &Vec<Vec<char>>, x: usize, y: usize, gears: &mut BTreeSet<(usize, usize)>) {
-    if x > 0 {
-        if is_gear(grid[y][x - 1]) {
-            gears.insert((x - 1, y));
-        }
-        if y > 0 && is_gear(grid[y - 1][x - 1]) {
-            gears.insert((x - 1, y - 1));
-        }
-        if y < grid.len() - 1 && is_gear(grid[y + 1][x - 1]) {
-            gears.insert((x - 1, y + 1));
-        }
-    }
-
-    if y > 0 && is_gear(grid[y - 1][x]) {
-        gears.insert((x, y - 1));
-    }
-    if y < grid.len() - 1 && is_gear(grid[y + 1][x]) {
-        gears.insert((x, y + 1));
-    }
-
-    if x < grid[y].len() - 1 {
-        if is_gear(grid[y][x + 1]) {
-            gears.insert((x + 1, y));
-        }
-        if y > 0 && is_gear(grid[y - 1][x + 1]) {
-            gears.insert((x + 1, y - 1));
-        }
-        if y < grid.len() - 1 && is_gear(grid[y + 1][x + 1]) {
-            gears.insert((x + 1, y + 1));
-        }
-    }
+// Returns a random grid of numbers and symbols. The size is the width and height of the grid.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            let mut row = String::new();
+            while row.len() < size {
+                if rng.chance(0.15) {
+                    let digits = rng.between(1, 3) as usize;
+                    let number = rng.between(1, 10i64.pow(digits as u32) - 1).to_string();
+                    row.push_str(&number[..number.len().min(size - row.len())]);
+                    if row.len() < size {
+                        row.push('.');
+                    }
+                } else if rng.chance(0.1) {
+                    row.push(*rng.choose(&['*', '#', '+', '$', '/', '=', '%', '@', '&', '-']));
+                } else {
+                    row.push('.');
+                }
+            }
+            row
+        })
+        .collect()
 }
 
-fn scan_number(grid: &Vec<Vec<char>>, x0: usize, y0: usize) -> (usize, u32, bool, BTreeSet<(usize, usize)>) {
-    let mut xn = x0;
-    let mut value = 0;
-    let mut is_part_number = false;
-    let mut adjacent_gears: BTreeSet<(usize, usize)> = BTreeSet::new();
-    while xn < grid[y0].len() && grid[y0][xn].is_ascii_digit() {
-        value = value * 10 + grid[y0][xn].to_digit(10).unwrap();
-        if is_adjacent_to_symbol(grid, xn, y0) {
-            is_part_number = true;
-            find_adjacent_gears(grid, xn, y0, &mut adjacent_gears);
-        }
-        xn = xn + 1;
-    }
-    (xn, value, is_part_number, adjacent_gears)
-}
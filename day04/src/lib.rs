@@ -0,0 +1,73 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::Problem;
+
+/// Parses the cards, one per line
+pub fn parse_cards(lines: &[String]) -> Result<Vec<Card>, Problem> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| Card::parse(line).map_err(|message| Problem::line(i, message)))
+        .collect()
+}
+
+/// A scratchcard: its id, and the winning numbers and your numbers, both sorted
+#[derive(Debug)]
+pub struct Card {
+    pub id: usize,
+    pub winning: Vec<i32>,
+    pub yours: Vec<i32>,
+}
+
+impl Card {
+    /// Parses "Card <id>: <winning numbers> | <your numbers>"
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let (card, numbers) = line.split_once(':').ok_or("missing ':'")?;
+        let id = card.strip_prefix("Card").ok_or("expected \"Card <id>\"")?.trim();
+        let id = id.parse().map_err(|_| format!("{:?} is not a card id", id))?;
+        let (winning, yours) = numbers.split_once('|').ok_or("missing '|'")?;
+        let parse_numbers = |s: &str| -> Result<Vec<i32>, String> {
+            let mut numbers = s
+                .split_whitespace()
+                .map(|token| token.parse().map_err(|_| format!("{:?} is not a number", token)))
+                .collect::<Result<Vec<i32>, String>>()?;
+            numbers.sort();
+            Ok(numbers)
+        };
+        Ok(Self { id, winning: parse_numbers(winning)?, yours: parse_numbers(yours)? })
+    }
+
+    /// Returns the number of your numbers that are winning numbers
+    pub fn matches(&self) -> usize {
+        intersection(&self.winning, &self.yours).len()
+    }
+
+    /// Returns the points the card is worth: 1 for the first match, doubled for each match after that
+    pub fn points(&self) -> u64 {
+        match self.matches() {
+            0 => 0,
+            matches => 1 << (matches - 1).min(63),
+        }
+    }
+}
+
+// Returns the intersection of two sorted vectors
+fn intersection(a: &Vec<i32>, b: &Vec<i32>) -> Vec<i32> {
+    let mut result = Vec::new();
+    let mut i = 0;
+    let mut j = 0;
+
+    while i < a.len() && j < b.len() {
+        if a[i] < b[j] {
+            i += 1;
+        } else if a[i] > b[j] {
+            j += 1;
+        } else {
+            result.push(a[i]);
+            i += 1;
+            j += 1;
+        }
+    }
+
+    result
+}
@@ -1,57 +1,84 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{args, debug, load};
+use day04::{parse_cards, Card};
 
 fn main() {
+    generate::run(200, generate);
+    validate::check(validate);
     println!("Day 4, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
     let lines = load::lines().unwrap();
 
     // Parse the cards
-    let mut cards: Vec<(i32, Vec<i32>, Vec<i32>)> = Vec::new();
+    let cards = validate::or_exit(parse_cards(&lines));
 
-    for line in lines {
-        let parts: Vec<&str> = line.split(":").collect();
-        let _id: i32 = parts[0].trim().split_whitespace().last().unwrap().parse().unwrap();
-
-        let sets: Vec<&str> = parts[1].split("|").collect();
-        let mut winning: Vec<i32> = sets[0].trim().split_whitespace().map(|s| s.parse().unwrap()).collect();
-        let mut yours: Vec<i32> = sets[1].trim().split_whitespace().map(|s| s.parse().unwrap()).collect();
-
-        winning.sort();
-        yours.sort();
-        cards.push((1, winning, yours));
-    }
-
-    // Find the winning cards and accumulate more cards
+    // Find the winning cards and accumulate more cards. Cards are never won past the end of the table.
+    let mut copies: Vec<u64> = vec![1; cards.len()];
+    let mut points = 0;
     let mut count = 0;
-    for i in 0..cards.len() {
-        count += cards[i].0;
-        let winners = intersection(&cards[i].1, &cards[i].2);
-        if winners.len() > 0 {
-            for j in i + 1..=i + winners.len() {
-                cards[j].0 += cards[i].0;
-            }
+    let report = args::flag("--report");
+    for (i, card) in cards.iter().enumerate() {
+        let matches = card.matches();
+        points += card.points();
+        count += copies[i];
+        debug!("Card {}: {} copies, {} matches", card.id, copies[i], matches);
+        if report {
+            println!("Card {}: {} matches, {} points, {} copies", card.id, matches, card.points(), copies[i]);
+        }
+        let won = copies[i];
+        for c in copies.iter_mut().skip(i + 1).take(matches) {
+            *c += won;
         }
     }
 
-    println!("Count: {}", count);
+    if cfg!(feature = "part2") {
+        println!("Count: {}", count);
+    } else {
+        println!("Points: {}", points);
+    }
 }
 
-// Returns the intersection of two sorted vectors
-fn intersection(a: &Vec<i32>, b: &Vec<i32>) -> Vec<i32> {
-    let mut result = Vec::new();
-    let mut i = 0;
-    let mut j = 0;
-
-    while i < a.len() && j < b.len() {
-        if a[i] < b[j] {
-            i += 1;
-        } else if a[i] > b[j] {
-            j += 1;
-        } else {
-            result.push(a[i]);
-            i += 1;
-            j += 1;
+// Returns the problems in the input. Each line is a card, and the cards are numbered from 1 in order.
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        match Card::parse(line) {
+            Ok(card) if card.id != i + 1 => {
+                problems.push(Problem::line(i, format!("expected card {}, found card {}", i + 1, card.id)))
+            }
+            Ok(_) => {}
+            Err(message) => problems.push(Problem::line(i, message)),
         }
     }
+    problems
+}
+
+// Returns random cards with 5 winning numbers and 8 of your numbers. A card never wins copies of cards past the end,
+// and no card has more than a million copies so that the count does not overflow. The size is the number of cards.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    const MAX_COPIES: i64 = 1_000_000;
 
-    result
+    let mut copies = vec![1; size];
+    (0..size)
+        .map(|i| {
+            let mut numbers: Vec<i64> = (1..100).collect();
+            rng.shuffle(&mut numbers);
+            let winning = &numbers[..5];
+            let mut matches = rng.below(5.min(size - 1 - i) + 1);
+            while copies[i + 1..=i + matches].iter().any(|&c| c + copies[i] > MAX_COPIES) {
+                matches -= 1;
+            }
+            for j in i + 1..=i + matches {
+                copies[j] += copies[i];
+            }
+            let mut yours: Vec<i64> = winning[..matches].iter().chain(&numbers[5..13 - matches]).cloned().collect();
+            rng.shuffle(&mut yours);
+            let format = |numbers: &[i64]| {
+                let numbers: Vec<String> = numbers.iter().map(|n| format!("{:2}", n)).collect();
+                numbers.join(" ")
+            };
+            format!("Card {:3}: {} | {}", i + 1, format(winning), format(&yours))
+        })
+        .collect()
 }
+
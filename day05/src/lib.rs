@@ -0,0 +1,146 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::Problem;
+
+/// A map entry: the start of the destination range, the start of the source range, and the size of the ranges
+pub type Entry = (i64, i64, i64);
+
+/// A map from numbers of one category to another, with its entries sorted by source
+#[derive(Debug)]
+pub struct Map {
+    pub source: String,
+    pub destination: String,
+    pub entries: Vec<Entry>,
+}
+
+impl Map {
+    /// Returns the destination number of a source number. Numbers not in any entry map to themselves.
+    pub fn forward(&self, value: i64) -> i64 {
+        self.entries
+            .iter()
+            .find(|&&(_, source, size)| (source..source + size).contains(&value))
+            .map_or(value, |&(destination, source, _)| destination + value - source)
+    }
+
+    /// Returns every source number that maps to a destination number
+    pub fn reverse(&self, value: i64) -> Vec<i64> {
+        let mut sources: Vec<i64> = self
+            .entries
+            .iter()
+            .filter(|&&(destination, _, size)| (destination..destination + size).contains(&value))
+            .map(|&(destination, source, _)| source + value - destination)
+            .collect();
+        if !self.entries.iter().any(|&(_, source, size)| (source..source + size).contains(&value)) {
+            sources.push(value);
+        }
+        sources.sort_unstable();
+        sources
+    }
+}
+
+/// The seed numbers and the maps between categories
+#[derive(Debug)]
+pub struct Almanac {
+    pub seeds: Vec<i64>,
+    pub maps: Vec<Map>,
+}
+
+impl Almanac {
+    /// Returns each seed number as a range of one seed, as (start, size)
+    pub fn individual_seeds(&self) -> Vec<(i64, i64)> {
+        self.seeds.iter().map(|&seed| (seed, 1)).collect()
+    }
+
+    /// Returns the seed numbers read as pairs of start and size. There must be an even number of them.
+    pub fn seed_ranges(&self) -> Result<Vec<(i64, i64)>, String> {
+        if !self.seeds.len().is_multiple_of(2) {
+            return Err("the seeds are not in pairs".to_string());
+        }
+        Ok(self.seeds.chunks(2).map(|c| (c[0], c[1])).collect())
+    }
+
+    /// Returns the maps that lead from one category to another, in order, or None if there is no such chain
+    pub fn chain(&self, from: &str, to: &str) -> Option<Vec<&Map>> {
+        let mut chain = Vec::new();
+        let mut category = from;
+        while category != to {
+            // A chain can't be longer than the number of maps unless it loops
+            if chain.len() == self.maps.len() {
+                return None;
+            }
+            let map = self.maps.iter().find(|map| map.source == category)?;
+            chain.push(map);
+            category = &map.destination;
+        }
+        Some(chain)
+    }
+
+    /// Returns the numbers in one category that correspond to a number in another. The lookup follows the maps
+    /// forward if `to` comes after `from`, and in reverse if it comes before, in which case there may be any number of
+    /// results.
+    pub fn lookup(&self, from: &str, to: &str, value: i64) -> Result<Vec<i64>, String> {
+        if let Some(chain) = self.chain(from, to) {
+            return Ok(vec![chain.iter().fold(value, |value, map| map.forward(value))]);
+        }
+        let chain = self.chain(to, from).ok_or_else(|| format!("no maps between {} and {}", from, to))?;
+        let mut values = vec![value];
+        for map in chain.iter().rev() {
+            values = values.iter().flat_map(|&value| map.reverse(value)).collect();
+        }
+        values.sort_unstable();
+        values.dedup();
+        Ok(values)
+    }
+}
+
+/// Parses the seed numbers on the first line and the maps after them
+pub fn parse_input(lines: &[String]) -> Result<Almanac, Problem> {
+    let first = lines.first().ok_or_else(|| Problem::line(0, "missing the seeds"))?;
+    let seeds = parse_seeds(first).map_err(|message| Problem::line(0, message))?;
+
+    let mut maps = Vec::new();
+    let mut map: Option<Map> = None;
+    for (i, line) in lines.iter().enumerate().skip(1) {
+        if line.trim().is_empty() {
+            maps.extend(map.take());
+        } else if let Some(map) = map.as_mut() {
+            map.entries.push(parse_entry(line).map_err(|message| Problem::line(i, message))?);
+        } else {
+            let (source, destination) = parse_header(line).map_err(|message| Problem::line(i, message))?;
+            map = Some(Map { source, destination, entries: Vec::new() });
+        }
+    }
+    maps.extend(map);
+
+    for map in &mut maps {
+        map.entries.sort_unstable_by_key(|e| e.1);
+    }
+    Ok(Almanac { seeds, maps })
+}
+
+/// Parses "seeds: <numbers>"
+pub fn parse_seeds(line: &str) -> Result<Vec<i64>, String> {
+    let numbers = line.strip_prefix("seeds:").ok_or("expected \"seeds: <numbers>\"")?;
+    parse_numbers(numbers)
+}
+
+/// Parses a map's header, such as "seed-to-soil map:", into its source and destination categories
+pub fn parse_header(line: &str) -> Result<(String, String), String> {
+    let names = line.strip_suffix(" map:").ok_or("expected \"<source>-to-<destination> map:\"")?;
+    let (source, destination) = names.split_once("-to-").ok_or("expected \"<source>-to-<destination> map:\"")?;
+    Ok((source.to_string(), destination.to_string()))
+}
+
+/// Parses a map entry of three numbers: destination start, source start, and size
+pub fn parse_entry(line: &str) -> Result<Entry, String> {
+    match parse_numbers(line)?[..] {
+        [destination, source, size] => Ok((destination, source, size)),
+        ref numbers => Err(format!("expected 3 numbers, found {}", numbers.len())),
+    }
+}
+
+fn parse_numbers(s: &str) -> Result<Vec<i64>, String> {
+    s.split_whitespace()
+        .map(|token| token.parse().map_err(|_| format!("{:?} is not a number", token)))
+        .collect()
+}
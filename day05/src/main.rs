@@ -1,60 +1,123 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{args, debug, load, trace};
+use day05::{parse_input, Almanac};
 
 fn main() {
+    generate::run(10, generate);
+    validate::check(validate);
     println!("Day 5, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
     let lines = load::lines().unwrap();
-    let mut iter = lines.iter();
 
-    // Load the seeds
-    let seeds = parse_seeds(&mut iter);
+    let almanac = validate::or_exit(parse_input(&lines));
+    debug!("Seeds: {:?}", almanac.seeds);
+    debug!("Maps: {}", almanac.maps.len());
 
-    // Load each map
-    let mut maps: Vec<Vec<(i64, i64, i64)>> = Vec::new();
-    while let Some(_line) = iter.next() {
-        // Ignore name of map
-        maps.push(parse_map(&mut iter));
+    if let Some(query) = args::value("--lookup") {
+        lookup(&almanac, &query);
+        return;
     }
 
+    // In part 1 each number is a seed, and in part 2 the numbers are pairs of start and size
+    let seeds = if cfg!(feature = "part2") {
+        validate::or_exit(almanac.seed_ranges().map_err(|message| Problem::line(0, message)))
+    } else {
+        almanac.individual_seeds()
+    };
+
+    let maps = almanac.chain("seed", "location").unwrap_or_else(|| panic!("No maps from seed to location"));
     let mut map = create_map_from_seeds(&seeds);
-    for m in &maps {
-        map = combine(&map, m);
+    for m in maps {
+        map = combine(&map, &m.entries);
+        trace!("Combined map ({} to {}): {:?}", m.source, m.destination, map);
     }
 
     println!("Min location: {}", map[0].0);
 }
 
-fn parse_seeds<'a, I>(iter: &mut I) -> Vec<(i64, i64)>
-where
-    I: Iterator<Item = &'a String>,
-{
-    let input = iter.next().unwrap();
-    let parts: Vec<&str> = input.split(":").collect();
-    let numbers: Vec<i64> = parts[1].trim().split_whitespace().map(|s| s.parse().unwrap()).collect();
-    let seeds = numbers.chunks(2).map(|c| (c[0], c[1])).collect();
-
-    iter.next(); // Skip blank line
-
-    seeds
+// Prints the numbers in one category that correspond to a number in another, given as "<from>:<to>:<number>"
+fn lookup(almanac: &Almanac, query: &str) {
+    let parts: Vec<&str> = query.split(':').collect();
+    let [from, to, value] = parts[..] else {
+        panic!("Invalid --lookup value: {}", query);
+    };
+    let value: i64 = value.parse().unwrap_or_else(|_| panic!("Invalid --lookup value: {}", query));
+    match almanac.lookup(from, to, value) {
+        Ok(values) => println!("{} {} -> {} {:?}", from, value, to, values),
+        Err(message) => println!("{}", message),
+    }
 }
 
-fn parse_map<'a, I>(iter: &mut I) -> Vec<(i64, i64, i64)>
-where
-    I: Iterator<Item = &'a String>,
-{
-    let mut map = Vec::new();
-
-    while let Some(line) = iter.next() {
-        if line.trim().is_empty() {
-            break;
+// Returns the problems in the input: a "seeds:" line with numbers (in pairs in part 2), then maps separated by blank
+// lines, each with a "<source>-to-<destination> map:" line followed by lines of three numbers. The maps must lead
+// from seed to location.
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    match lines.first().map(|line| day05::parse_seeds(line)) {
+        Some(Ok(seeds)) if cfg!(feature = "part2") && !seeds.len().is_multiple_of(2) => {
+            problems.push(Problem::line(0, "the seeds are not in pairs"))
         }
-
-        let parts: Vec<i64> = line.split_whitespace().map(|s| s.parse().unwrap()).collect();
-
-        map.push((parts[0], parts[1], parts[2]));
+        Some(Ok(_)) => {}
+        Some(Err(message)) => problems.push(Problem::line(0, message)),
+        None => problems.push(Problem::line(0, "expected \"seeds: <numbers>\"")),
     }
+    let mut in_map = false;
+    for (i, line) in lines.iter().enumerate().skip(1) {
+        let result = if line.trim().is_empty() {
+            in_map = false;
+            Ok(())
+        } else if !in_map {
+            in_map = true;
+            day05::parse_header(line).map(|_| ())
+        } else {
+            day05::parse_entry(line).map(|_| ())
+        };
+        if let Err(message) = result {
+            problems.push(Problem::line(i, message));
+        }
+    }
+    if problems.is_empty() {
+        match parse_input(lines) {
+            Ok(almanac) if almanac.chain("seed", "location").is_none() => {
+                problems.push(Problem::line(0, "the maps do not lead from seed to location"))
+            }
+            Ok(_) => {}
+            Err(problem) => problems.push(problem),
+        }
+    }
+    problems
+}
 
-    map.sort_unstable_by(|a, b| a.1.cmp(&b.1));
-    map
+// Returns random seed ranges and maps. Each map rearranges the numbers 0 to 10^9 in randomly sized ranges. The size
+// is the number of seed ranges and of ranges in each map.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    const LIMIT: i64 = 1_000_000_000;
+    const CATEGORIES: [&str; 8] =
+        ["seed", "soil", "fertilizer", "water", "light", "temperature", "humidity", "location"];
+
+    let seeds: Vec<String> = (0..size)
+        .map(|_| {
+            let start = rng.between(0, LIMIT - 1);
+            format!("{} {}", start, rng.between(1, (LIMIT - start).min(LIMIT / 10)))
+        })
+        .collect();
+    let mut lines = vec![format!("seeds: {}", seeds.join(" "))];
+    for names in CATEGORIES.windows(2) {
+        let mut cuts: Vec<i64> = (1..size).map(|_| rng.between(1, LIMIT - 1)).collect();
+        cuts.extend([0, LIMIT]);
+        cuts.sort_unstable();
+        cuts.dedup();
+        let mut ranges: Vec<(i64, i64)> = cuts.windows(2).map(|w| (w[0], w[1] - w[0])).collect();
+        rng.shuffle(&mut ranges);
+        lines.push(String::new());
+        lines.push(format!("{}-to-{} map:", names[0], names[1]));
+        let mut destination = 0;
+        for (source, length) in ranges {
+            lines.push(format!("{} {} {}", destination, source, length));
+            destination += length;
+        }
+    }
+    lines
 }
 
 fn create_map_from_seeds(seeds: &Vec<(i64, i64)>) -> Vec<(i64, i64, i64)> {
@@ -79,7 +142,7 @@ fn combine(map1: &Vec<(i64, i64, i64)>, map2: &Vec<(i64, i64, i64)>) -> Vec<(i64
 
             // Create an entry for the map 1 entry range that is before the map 2 entry range
             if dst_start(&e) < src_start(e2) {
-                let part1_size = src_start(e2) - dst_start(&e);
+                let part1_size = std::cmp::min(src_start(e2) - dst_start(&e), size(&e));
                 new_map.push((dst_start(&e), src_start(&e), part1_size));
                 e = (dst_start(&e) + part1_size, src_start(&e) + part1_size, size(&e) - part1_size);
             }
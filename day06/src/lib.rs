@@ -0,0 +1,25 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::Problem;
+
+/// Returns the time and record distance of each race. In part 2, the digits on each line form a single number.
+pub fn parse_races(lines: &[String]) -> Result<Vec<(u64, u64)>, Problem> {
+    let mut columns = Vec::new();
+    for (i, label) in ["Time:", "Distance:"].iter().enumerate() {
+        let numbers = lines
+            .get(i)
+            .and_then(|line| line.strip_prefix(label))
+            .ok_or_else(|| Problem::line(i, format!("expected \"{} <numbers>\"", label)))?;
+        let numbers = if cfg!(feature = "part2") { numbers.replace(' ', "") } else { numbers.to_string() };
+        let numbers = numbers
+            .split_whitespace()
+            .map(|token| token.parse().map_err(|_| Problem::line(i, format!("{:?} is not a number", token))))
+            .collect::<Result<Vec<u64>, Problem>>()?;
+        columns.push(numbers);
+    }
+    if columns[0].len() != columns[1].len() {
+        let message = format!("expected {} distances, found {}", columns[0].len(), columns[1].len());
+        return Err(Problem::line(1, message));
+    }
+    Ok(columns[0].iter().copied().zip(columns[1].iter().copied()).collect())
+}
@@ -1,24 +1,122 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{debug, load};
+use day06::parse_races;
+use std::process;
 
 fn main() {
+    generate::run(4, generate);
+    validate::check(validate);
     println!("Day 6, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
     let lines = load::lines().unwrap();
 
-    let times = parse_line(&lines[0]);
-    let distances = parse_line(&lines[1]);
-    let r = (times, distances);
-    let mut lower = ((r.0 - (r.0 * r.0 - 4.0 * r.1).sqrt()) / 2.0).ceil();
-    if (r.0 - lower) * lower <= r.1 {
-        lower += 1.0;
+    let races = validate::or_exit(parse_races(&lines));
+    let mut product: Option<u64> = Some(1);
+    for (time, distance) in races {
+        debug!("Time: {}, distance: {}", time, distance);
+        let ways = match winning_holds(time, distance) {
+            Some((lower, upper)) => {
+                debug!("Holds {} to {}, {} ways to win", lower, upper, upper - lower + 1);
+                upper - lower + 1
+            }
+            None => {
+                debug!("The race cannot be won");
+                0
+            }
+        };
+        product = product.and_then(|product| product.checked_mul(ways));
     }
-    let mut upper = ((r.0 + (r.0 * r.0 - 4.0 * r.1).sqrt()) / 2.0).ceil();
-    if (r.0 - upper) * upper <= r.1 {
-        upper -= 1.0;
+    let Some(product) = product else {
+        eprintln!("The product of the ways to win is more than {}", u64::MAX);
+        process::exit(1);
+    };
+    if cfg!(feature = "part2") {
+        println!("Ways to win: {}", product);
+    } else {
+        println!("Product: {}", product);
     }
-    println!("{} {} {}", lower, upper, upper - lower + 1.0);
 }
 
-fn parse_line(line: &String) -> f64 {
-    let data = line.split(":").nth(1).unwrap().replace(" ", "").parse::<f64>().unwrap();
-    data
+// Returns the problems in the input, which is a "Time:" line and a "Distance:" line with the same number of numbers
+fn validate(lines: &[String]) -> Vec<Problem> {
+    parse_races(lines).err().into_iter().collect()
+}
+
+// Returns random races that can each be won. The times have two digits and the first is at least 32, and the record
+// distances have at most three digits, so the race formed by joining the digits can be won too. The size is the number
+// of races, from 1 to 6 so that the joined distance fits in 64 bits.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let races: Vec<(i64, i64)> = (0..size.clamp(1, 6))
+        .map(|i| {
+            let time = rng.between(if i == 0 { 32 } else { 10 }, 99);
+            (time, rng.between(0, (time / 2 * (time - time / 2) - 1).min(999)))
+        })
+        .collect();
+    let times: Vec<String> = races.iter().map(|race| format!("{:5}", race.0)).collect();
+    let distances: Vec<String> = races.iter().map(|race| format!("{:5}", race.1)).collect();
+    vec![format!("Time:    {}", times.join("")), format!("Distance:{}", distances.join(""))]
+}
+
+// Returns true if holding the button for `hold` beats the record distance
+fn wins(time: u64, distance: u64, hold: u64) -> bool {
+    hold <= time && hold as u128 * (time - hold) as u128 > distance as u128
+}
+
+// Returns the shortest and longest times to hold the button that beat the record distance, or None if no time does.
+//
+// The winning holds are the whole numbers strictly between the roots of (time - hold) * hold = distance, which are
+// (time ± √d) / 2 where d = time² - 4 * distance. With s = ⌊√d⌋, s + 1 > √d, so (time - s - 1) / 2 is below the lower
+// root and does not win. The shortest winning hold is found by stepping up from there, which takes at most a couple of
+// steps. The distance is symmetric about time / 2, so the longest winning hold is time minus the shortest.
+fn winning_holds(time: u64, distance: u64) -> Option<(u64, u64)> {
+    let discriminant = (time as u128 * time as u128).checked_sub(4 * distance as u128)?;
+    let s = discriminant.isqrt();
+    let mut lower = ((time as u128).saturating_sub(s + 1) / 2) as u64;
+    while !wins(time, distance, lower) {
+        // The longest distance is from holding for time / 2
+        if lower >= time / 2 {
+            return None;
+        }
+        lower += 1;
+    }
+    Some((lower, time - lower))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::verify;
+
+    #[test]
+    fn ways_to_win() {
+        verify::property(100, 100, check_ways_to_win);
+    }
+
+    // Checks winning_holds against trying every hold time in a random race, including races that cannot be won. It also
+    // checks that the holds found in a race with large numbers are exactly the boundaries of the winning holds.
+    fn check_ways_to_win(rng: &mut Rng, size: usize) -> Result<(), String> {
+        let time = rng.between(0, 10 * size as i64) as u64;
+        let distance = rng.between(0, (time * time / 4 + 1) as i64) as u64;
+        let expected = (0..=time).filter(|&hold| wins(time, distance, hold)).count() as u64;
+        let actual = winning_holds(time, distance).map_or(0, |(lower, upper)| upper - lower + 1);
+        if actual != expected {
+            return Err(format!("time {}, distance {}: {} ways, expected {}", time, distance, actual, expected));
+        }
+
+        let time = rng.next_u64();
+        let best = (time / 2) as u128 * (time - time / 2) as u128;
+        let distance = (best - best / (1 + rng.below(1000) as u128)).min(u64::MAX as u128) as u64;
+        match winning_holds(time, distance) {
+            Some((lower, upper))
+                if wins(time, distance, lower)
+                    && wins(time, distance, upper)
+                    && (lower == 0 || !wins(time, distance, lower - 1))
+                    && (upper == time || !wins(time, distance, upper + 1)) =>
+            {
+                Ok(())
+            }
+            None if !wins(time, distance, time / 2) => Ok(()),
+            result => Err(format!("time {}, distance {}: found {:?}", time, distance, result)),
+        }
+    }
 }
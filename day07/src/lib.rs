@@ -0,0 +1,22 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::Problem;
+
+/// Parses the hands and bids, one per line, such as "32T3K 765"
+pub fn parse_hands(lines: &[String]) -> Result<Vec<(Vec<char>, i64)>, Problem> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| parse_line(line).map_err(|message| Problem::line(i, message)))
+        .collect()
+}
+
+/// Parses a hand of cards and its bid
+pub fn parse_line(line: &str) -> Result<(Vec<char>, i64), String> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let [hand, bid] = fields[..] else {
+        return Err(format!("expected a hand and a bid, found {} fields", fields.len()));
+    };
+    let bid = bid.parse().map_err(|_| format!("{:?} is not a bid", bid))?;
+    Ok((hand.chars().collect(), bid))
+}
@@ -1,211 +1,247 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{args, debug, load};
+use day07::{parse_hands, parse_line};
+use std::fs;
+use std::process;
+
+// The hand types from strongest to weakest, with the sizes of the groups of matching cards that make them
+const STANDARD_TYPES: [(&str, &[usize]); 7] = [
+    ("five of a kind", &[5]),
+    ("four of a kind", &[4]),
+    ("full house", &[3, 2]),
+    ("three of a kind", &[3]),
+    ("two pair", &[2, 2]),
+    ("one pair", &[2]),
+    ("high card", &[1]),
+];
+
+// The rules of a game of Camel Cards
+#[derive(Debug)]
+struct Ruleset {
+    // The cards from strongest to weakest
+    order: Vec<char>,
+    // Cards that act like whichever card makes the hand strongest
+    wildcards: Vec<char>,
+    // The hand types from strongest to weakest, each with the sizes of its groups of matching cards, largest first
+    types: Vec<(String, Vec<usize>)>,
+}
+
+impl Ruleset {
+    // Returns the rules of part 1, where J is a jack
+    fn standard() -> Self {
+        Self::parse("order AKQJT98765432").unwrap()
+    }
+
+    // Returns the rules of part 2, where J is a joker and the weakest card
+    fn jokers() -> Self {
+        Self::parse("order AKQT98765432J\nwild J").unwrap()
+    }
+
+    // Parses rules from lines of "order <cards>", "wild <cards>", and "type <name...> <group sizes...>". The order is
+    // required, and the standard hand types are used if none are given.
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut order = Vec::new();
+        let mut wildcards = Vec::new();
+        let mut types = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[..] {
+                [] => {}
+                ["order", cards] => order = cards.chars().collect(),
+                ["wild", cards] => wildcards = cards.chars().collect(),
+                ["type", ref rest @ ..] => {
+                    let sizes = rest.iter().rev().map_while(|field| field.parse().ok()).collect::<Vec<usize>>();
+                    let name = rest[..rest.len() - sizes.len()].join(" ");
+                    if name.is_empty() || sizes.is_empty() {
+                        return Err(format!("line {}: expected \"type <name> <group sizes>\"", i + 1));
+                    }
+                    let mut sizes = sizes;
+                    sizes.sort_unstable_by(|a, b| b.cmp(a));
+                    types.push((name, sizes));
+                }
+                _ => return Err(format!("line {}: unknown rule {:?}", i + 1, line)),
+            }
+        }
+        if order.is_empty() {
+            return Err("missing the order of the cards".to_string());
+        }
+        if let Some(c) = wildcards.iter().find(|c| !order.contains(c)) {
+            return Err(format!("the wildcard {:?} is not in the order", c));
+        }
+        if types.is_empty() {
+            types = STANDARD_TYPES.iter().map(|(name, sizes)| (name.to_string(), sizes.to_vec())).collect();
+        }
+        Ok(Self { order, wildcards, types })
+    }
+
+    // Returns the rules given with --rules, which is "standard", "jokers", or a file of rules. The default is the
+    // rules of the part. It reports a file that cannot be read or parsed and exits.
+    fn from_args() -> Self {
+        match args::value("--rules").as_deref() {
+            Some("standard") => Self::standard(),
+            Some("jokers") => Self::jokers(),
+            Some(path) => {
+                let rules = fs::read_to_string(path)
+                    .map_err(|e| format!("Could not read \"{}\": {}", path, e))
+                    .and_then(|text| Self::parse(&text).map_err(|e| format!("Invalid rules \"{}\": {}", path, e)));
+                rules.unwrap_or_else(|message| {
+                    eprintln!("{}", message);
+                    process::exit(1);
+                })
+            }
+            None if cfg!(feature = "part2") => Self::jokers(),
+            None => Self::standard(),
+        }
+    }
+
+    // Returns the index of the hand's type, where 0 is the strongest. The wildcards join the largest group of matching
+    // cards, and the type is the first one whose groups the hand's groups cover. A hand that matches no type is the
+    // weakest type.
+    fn classify(&self, hand: &[char]) -> usize {
+        let mut groups: Vec<usize> = Vec::new();
+        let mut cards: Vec<char> = hand.iter().filter(|c| !self.wildcards.contains(c)).copied().collect();
+        cards.sort_unstable();
+        for run in cards.chunk_by(|a, b| a == b) {
+            groups.push(run.len());
+        }
+        groups.sort_unstable_by(|a, b| b.cmp(a));
+        let wild = hand.len() - cards.len();
+        match groups.first_mut() {
+            Some(largest) => *largest += wild,
+            None => groups.push(wild),
+        }
+        self.types
+            .iter()
+            .position(|(_, sizes)| sizes.iter().enumerate().all(|(i, &size)| groups.get(i).is_some_and(|&g| g >= size)))
+            .unwrap_or(self.types.len() - 1)
+    }
 
-const SORT_ORDER: [char; 13] = ['A', 'K', 'Q', 'T', '9', '8', '7', '6', '5', '4', '3', '2', 'J'];
+    // Returns the card's strength, where 0 is the strongest
+    fn strength(&self, card: char) -> usize {
+        self.order.iter().position(|&c| c == card).unwrap()
+    }
+
+    // Returns the key that orders hands from strongest to weakest, by type and then card by card
+    fn sort_key(&self, hand: &[char]) -> (usize, Vec<usize>) {
+        (self.classify(hand), hand.iter().map(|&c| self.strength(c)).collect())
+    }
+}
 
 fn main() {
+    generate::run(1000, generate);
+    validate::check(validate);
     println!("Day 7, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
     let lines = load::lines().unwrap();
+    let rules = Ruleset::from_args();
 
-    let mut game: Vec<(Vec<char>, i64)> = Vec::new();
-    for line in lines {
-        let play = parse_line(&line);
-        game.push(play);
-    }
-
-    game.sort_unstable_by(|a, b| hand_sorter(&a.0, &b.0));
+    let mut game: Vec<(Vec<char>, i64)> = validate::or_exit(parse_hands(&lines));
+    game.sort_by_cached_key(|(hand, _)| rules.sort_key(hand));
 
     let mut sum: i64 = 0;
-    for i in 0..game.len() {
-        let bid = game[i].1;
+    for (i, (hand, bid)) in game.iter().enumerate() {
         let rank = (game.len() - i) as i64;
+        let hand_type = &rules.types[rules.classify(hand)].0;
+        debug!("{}: {}, bid {}, rank {}", hand.iter().collect::<String>(), hand_type, bid, rank);
         sum += bid * rank;
     }
 
     println!("Sum: {}", sum);
 }
 
-fn card_sorter(a: &char, b: &char) -> std::cmp::Ordering {
-    let ia = SORT_ORDER.iter().position(|&x| x == *a).unwrap();
-    let ib = SORT_ORDER.iter().position(|&x| x == *b).unwrap();
-    ia.cmp(&ib)
-}
-
-fn hand_sorter(a: &Vec<char>, b: &Vec<char>) -> std::cmp::Ordering {
-    let a_type = classify(a);
-    let b_type = classify(b);
-    if a_type != b_type {
-        return a_type.cmp(&b_type);
-    }
-
-    // Same type, sort by value
-    for i in 0..a.len() {
-        if a[i] != b[i] {
-            return card_sorter(&a[i], &b[i]);
+// Returns the problems in the input. Each line is a hand of cards in the rules and a bid, and every hand has the same
+// number of cards.
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let rules = Ruleset::from_args();
+    let mut problems = Vec::new();
+    let mut hand_size = None;
+    for (i, line) in lines.iter().enumerate() {
+        let (hand, _) = match parse_line(line) {
+            Ok(parsed) => parsed,
+            Err(message) => {
+                problems.push(Problem::line(i, message));
+                continue;
+            }
+        };
+        if hand.len() != *hand_size.get_or_insert(hand.len()) {
+            problems.push(Problem::line(i, format!("expected {} cards, found {}", hand_size.unwrap(), hand.len())));
+        }
+        let start = line.len() - line.trim_start().len();
+        for (j, c) in hand.iter().enumerate() {
+            if !rules.order.contains(c) {
+                problems.push(Problem::at(i, start + j, format!("unknown card {:?}", c)));
+            }
         }
     }
-
-    return std::cmp::Ordering::Equal;
+    problems
 }
 
-fn parse_line(line: &String) -> (Vec<char>, i64) {
-    let parts: Vec<&str> = line.trim().split_whitespace().collect();
-    let hand = parts[0].chars().collect();
-    let bid = parts[1].parse().unwrap();
-    (hand, bid)
+// Returns random hands of 5 cards and bids. The size is the number of hands.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let cards = Ruleset::standard().order;
+    (0..size)
+        .map(|_| {
+            let hand: String = (0..5).map(|_| *rng.choose(&cards)).collect();
+            format!("{} {}", hand, rng.between(1, 1000))
+        })
+        .collect()
 }
 
-// Returns the type of hand
-fn classify(hand: &Vec<char>) -> i64 {
-    let mut sorted = hand.clone();
-    sorted.sort_unstable_by(|a, b| card_sorter(a, b));
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if is_five_of_a_kind(&sorted) {
-        return 0;
-    }
-    if is_four_of_a_kind(&sorted) {
-        return 1;
-    } else if is_full_house(&sorted) {
-        return 2;
-    } else if is_three_of_a_kind(&sorted) {
-        return 3;
-    } else if is_two_pair(&sorted) {
-        return 4;
-    } else if is_pair(&sorted) {
-        return 5;
-    }
-
-    return 6;
-}
+    const EXAMPLE: [&str; 5] = ["32T3K", "T55J5", "KK677", "KTJJT", "QQQJA"];
 
-// Returns true if the hand is a four of a kind
-fn is_five_of_a_kind(hand: &Vec<char>) -> bool {
-    let c = hand[0];
-    for card in hand {
-        if *card != c && *card != 'J' {
-            return false;
-        }
+    // Returns the example hands from strongest to weakest under the rules
+    fn ranked(rules: &Ruleset) -> Vec<&'static str> {
+        let mut hands = EXAMPLE.to_vec();
+        hands.sort_by_cached_key(|hand| rules.sort_key(&hand.chars().collect::<Vec<char>>()));
+        hands
     }
-    return true;
-}
 
-// Returns true if the hand is a four of a kind
-fn is_four_of_a_kind(hand: &Vec<char>) -> bool {
-    let mut count = 0;
-    let mut last = ' ';
-    let number_of_jokers = hand.iter().filter(|&x| *x == 'J').count() as i32;
-    for card in hand {
-        if *card == last {
-            count += 1;
-        } else if *card == 'J' {
-            continue;
-        } else {
-            count = 1;
-        }
-        last = *card;
-        if count + number_of_jokers == 4 {
-            return true;
-        }
+    // Returns the name of the hand's type under the rules
+    fn type_name<'a>(rules: &'a Ruleset, hand: &str) -> &'a str {
+        &rules.types[rules.classify(&hand.chars().collect::<Vec<char>>())].0
     }
-    return false;
-}
 
-// Returns true if the hand is a full house
-fn is_full_house(hand: &Vec<char>) -> bool {
-    let mut count1 = 0;
-    let mut count2 = 0;
-    let mut last1 = ' ';
-    let mut last2 = ' ';
-    for card in hand {
-        if *card == last1 {
-            count1 += 1;
-        } else if *card == last2 {
-            count2 += 1;
-        } else if count1 == 0 {
-            count1 = 1;
-            last1 = *card;
-        } else if count2 == 0 {
-            count2 = 1;
-            last2 = *card;
-        } else if *card == 'J' {
-            continue;
-        } else {
-            return false;
-        }
+    #[test]
+    fn standard_ranks_the_example() {
+        let rules = Ruleset::standard();
+        assert_eq!(ranked(&rules), ["QQQJA", "T55J5", "KK677", "KTJJT", "32T3K"]);
+        assert_eq!(type_name(&rules, "KTJJT"), "two pair");
     }
-    true
-}
 
-// Returns true if the hand is a three of a kind
-fn is_three_of_a_kind(hand: &Vec<char>) -> bool {
-    let mut count = 0;
-    let mut last = ' ';
-    let number_of_jokers = hand.iter().filter(|&x| *x == 'J').count() as i32;
-    for card in hand {
-        if *card == last {
-            count += 1;
-        } else if *card == 'J' {
-            continue;
-        } else {
-            count = 1;
-        }
-        last = *card;
-        if count + number_of_jokers == 3 {
-            return true;
-        }
+    #[test]
+    fn jokers_rank_the_example() {
+        let rules = Ruleset::jokers();
+        assert_eq!(ranked(&rules), ["KTJJT", "QQQJA", "T55J5", "KK677", "32T3K"]);
+        assert_eq!(type_name(&rules, "KTJJT"), "four of a kind");
+        assert_eq!(type_name(&rules, "JJJJJ"), "five of a kind");
+        // A joker is the weakest card when breaking ties
+        assert!(rules.sort_key(&['J', 'K', 'K', 'K', '2']) > rules.sort_key(&['Q', 'Q', 'Q', 'Q', '2']));
     }
-    return false;
-}
 
-// Returns true if the hand is a two pair
-fn is_two_pair(hand: &Vec<char>) -> bool {
-    let mut count1 = 0;
-    let mut count2 = 0;
-    let mut count3 = 0;
-    let mut last1 = ' ';
-    let mut last2 = ' ';
-    let mut last3 = ' ';
-    for card in hand {
-        if *card == last1 {
-            count1 += 1;
-        } else if *card == last2 {
-            count2 += 1;
-        } else if *card == last3 {
-            count3 += 1;
-        } else if count1 == 0 {
-            count1 = 1;
-            last1 = *card;
-        } else if count2 == 0 {
-            count2 = 1;
-            last2 = *card;
-        } else if count3 == 0 {
-            count3 = 1;
-            last3 = *card;
-        } else if *card == 'J' {
-            continue;
-        } else {
-            return false;
-        }
+    #[test]
+    fn custom_types() {
+        let rules = Ruleset::parse("order ABC\nwild C\ntype triple 3\ntype two pairs 2 2\ntype pair 2\ntype nothing 1")
+            .unwrap();
+        assert_eq!(rules.order, ['A', 'B', 'C']);
+        assert_eq!(rules.wildcards, ['C']);
+        assert_eq!(rules.types[1], ("two pairs".to_string(), vec![2, 2]));
+        assert_eq!(type_name(&rules, "AAB"), "pair");
+        assert_eq!(type_name(&rules, "ACB"), "pair");
+        assert_eq!(type_name(&rules, "ABAB"), "two pairs");
+        assert_eq!(type_name(&rules, "AAAB"), "triple");
+        assert_eq!(type_name(&rules, "AB"), "nothing");
+        assert!(rules.sort_key(&['A', 'A', 'B']) < rules.sort_key(&['A', 'B', 'B']));
     }
-    true
-}
 
-// Returns true if the hand is a pair
-fn is_pair(hand: &Vec<char>) -> bool {
-    let mut count = 0;
-    let mut last = ' ';
-    let number_of_jokers = hand.iter().filter(|&x| *x == 'J').count() as i32;
-    for card in hand {
-        if *card == last {
-            count += 1;
-        } else if *card == 'J' {
-            continue;
-        } else {
-            count = 1;
-        }
-        last = *card;
-        if count + number_of_jokers == 2 {
-            return true;
-        }
+    #[test]
+    fn invalid_rules() {
+        assert!(Ruleset::parse("wild J").is_err());
+        assert!(Ruleset::parse("order AK\nwild J").is_err());
+        assert!(Ruleset::parse("order AK\ntype 2").is_err());
+        assert!(Ruleset::parse("order AK\ncolor red").is_err());
     }
-    return false;
 }
@@ -0,0 +1,39 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::Problem;
+use regex::Regex;
+use std::collections::HashMap;
+
+/// The left and right neighbors of each node, by name
+pub type Graph = HashMap<String, (String, String)>;
+
+/// Parses a line of 'L' and 'R' directions, a blank line, then nodes such as "AAA = (BBB, CCC)". Every neighbor must
+/// be a defined node.
+pub fn parse_network(lines: &[String]) -> Result<(Vec<char>, Graph), Problem> {
+    let path: Vec<char> = match lines.first() {
+        Some(line) if !line.is_empty() => line.chars().collect(),
+        _ => return Err(Problem::line(0, "missing the directions")),
+    };
+    if let Some(j) = path.iter().position(|&c| c != 'L' && c != 'R') {
+        return Err(Problem::at(0, j, format!("unexpected direction {:?}", path[j])));
+    }
+    if lines.get(1).is_some_and(|line| !line.is_empty()) {
+        return Err(Problem::line(1, "expected a blank line"));
+    }
+
+    let graph_regex = Regex::new(r"^(\w+) = \((\w+), (\w+)\)$").unwrap();
+    let mut graph = Graph::new();
+    let mut neighbors = Vec::new();
+    for (i, line) in lines.iter().enumerate().skip(2) {
+        let captures = graph_regex
+            .captures(line)
+            .ok_or_else(|| Problem::line(i, "expected \"<node> = (<left>, <right>)\""))?;
+        graph.insert(captures[1].to_string(), (captures[2].to_string(), captures[3].to_string()));
+        neighbors.push((i, captures[2].to_string()));
+        neighbors.push((i, captures[3].to_string()));
+    }
+    if let Some((i, neighbor)) = neighbors.into_iter().find(|(_, neighbor)| !graph.contains_key(neighbor)) {
+        return Err(Problem::line(i, format!("node {:?} is not defined", neighbor)));
+    }
+    Ok((path, graph))
+}
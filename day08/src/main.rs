@@ -1,90 +1,190 @@
-use common::load;
-use regex::Regex;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{debug, load, math};
+use day08::{parse_network, Graph};
 use std::collections::HashMap;
+use std::process;
+
+// The steps at which a walk through the network is at an end node. The walk's state is its node and its position in
+// the directions, so it eventually repeats: from step `start`, everything repeats every `length` steps.
+#[derive(Debug)]
+struct Cycle {
+    // The steps before the cycle at an end node
+    transient_hits: Vec<i64>,
+    start: i64,
+    length: i64,
+    // The steps in the first repetition of the cycle at an end node
+    hits: Vec<i64>,
+}
+
+impl Cycle {
+    // Returns true if the walk is at an end node after the number of steps
+    fn hits_at(&self, step: i64) -> bool {
+        if step < self.start {
+            self.transient_hits.contains(&step)
+        } else {
+            self.hits.contains(&(self.start + (step - self.start) % self.length))
+        }
+    }
+
+    // Returns the first step at an end node, if the walk ever reaches one
+    fn first_hit(&self) -> Option<i64> {
+        self.transient_hits.first().or(self.hits.first()).copied()
+    }
+}
 
 fn main() {
+    generate::run(6, generate);
+    validate::check(validate);
     println!("Day 8, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
     let lines = load::lines().unwrap();
 
-    // Load the path
-    let path: Vec<char> = lines[0].chars().collect();
-
-    // Load the graph
-    let mut graph: HashMap<String, (String, String)> = HashMap::new();
-    let graph_regex = Regex::new(r"(\w+)\s*=\s*\((\w+),\s*(\w+)\)").unwrap();
-    for line in &lines[2..] {
-        if let Some(captures) = graph_regex.captures(line) {
-            let node = captures.get(1).map(|m| m.as_str().to_string());
-            let left = captures.get(2).map(|m| m.as_str().to_string());
-            let right = captures.get(3).map(|m| m.as_str().to_string());
-            graph.insert(node.unwrap(), (left.unwrap(), right.unwrap()));
+    let (path, graph) = validate::or_exit(parse_network(&lines));
+
+    debug!("Path length: {}, nodes: {}", path.len(), graph.len());
+
+    let steps = if cfg!(feature = "part2") {
+        // Each ghost starts at a node ending in 'A' and the ghosts stop when they are all at nodes ending in 'Z'
+        let mut ghosts: Vec<&String> = graph.keys().filter(|node| node.ends_with('A')).collect();
+        ghosts.sort();
+        let cycles: Vec<Cycle> = ghosts
+            .iter()
+            .map(|ghost| {
+                let cycle = find_cycle(&graph, &path, ghost, |node| node.ends_with('Z'));
+                debug!("{}: {:?}", ghost, cycle);
+                cycle
+            })
+            .collect();
+        first_common_step(&cycles).ok_or("The ghosts are never all at nodes ending in 'Z' at the same time")
+    } else {
+        let cycle = find_cycle(&graph, &path, "AAA", |node| node == "ZZZ");
+        debug!("AAA: {:?}", cycle);
+        cycle.first_hit().ok_or("ZZZ is never reached from AAA")
+    };
+
+    match steps {
+        Ok(steps) => println!("Steps: {}", steps),
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(1);
         }
     }
+}
 
-    // Find the node names ending in 'A'
-    let mut ghosts: Vec<String> = Vec::new();
-    for node_name in graph.keys() {
-        if node_name.chars().nth(2).unwrap() == 'A' {
-            ghosts.push(node_name.clone());
+// Walks from the start node until the walk repeats, and returns the steps at which it is at an end node
+fn find_cycle(
+    graph: &Graph,
+    path: &[char],
+    start: &str,
+    is_end: impl Fn(&str) -> bool,
+) -> Cycle {
+    let mut first_seen: HashMap<(&str, usize), i64> = HashMap::new();
+    let mut ends = Vec::new();
+    let mut node = start;
+    let mut count: i64 = 0;
+    loop {
+        let position = count as usize % path.len();
+        if let Some(&cycle_start) = first_seen.get(&(node, position)) {
+            let (transient_hits, hits) = ends.iter().partition(|&&step| step < cycle_start);
+            return Cycle { transient_hits, start: cycle_start, length: count - cycle_start, hits };
+        }
+        first_seen.insert((node, position), count);
+        if count > 0 && is_end(node) {
+            ends.push(count);
         }
+        node = step(graph, node, path[position]);
+        count += 1;
     }
+}
 
-    // Put the ghosts at their starting nodes
-    let mut ghost_node_names: Vec<&String> = Vec::new();
-    for node_name in &ghosts {
-        ghost_node_names.push(node_name)
-    }
+// Returns the first step after the start at which every walk is at an end node, or None if there is no such step
+fn first_common_step(cycles: &[Cycle]) -> Option<i64> {
+    // A step before some walk's cycle starts must be one of that walk's transient hits
+    let transient = cycles
+        .iter()
+        .flat_map(|cycle| cycle.transient_hits.iter().copied())
+        .filter(|&step| cycles.iter().all(|cycle| cycle.hits_at(step)))
+        .min();
 
-    struct Stat {
-        end: String,
-        first: i32,
-        second: i32,
+    // Otherwise every walk is in its cycle. Merge the walks one at a time, keeping the residues (x, m) such that all
+    // the walks so far are at end nodes at the steps x + k * m, and dropping the hits that never align.
+    let mut residues: Vec<(i64, i64)> = vec![(0, 1)];
+    for cycle in cycles {
+        residues = residues
+            .iter()
+            .flat_map(|&residue| cycle.hits.iter().filter_map(move |&hit| math::crt(&[residue, (hit, cycle.length)])))
+            .collect();
+        residues.sort_unstable();
+        residues.dedup();
     }
 
-    let mut stats: Vec<Stat> = Vec::new();
-
-    for ghost in ghosts {
-        let mut stat = Stat {
-            end: String::new(),
-            first: 0,
-            second: 0,
-        };
-
-        let mut count: usize = 0;
-        let mut done = false;
-        let mut node_name = &ghost;
-        while !done {
-            let direction = path[count % path.len()];
-            node_name = step(&graph, node_name, direction);
-            count += 1;
-            if node_name.chars().nth(2).unwrap() == 'Z' {
-                if stat.first == 0 {
-                    stat.end = node_name.clone();
-                    stat.first = count as i32;
-                } else {
-                    stat.second = count as i32;
-                    done = true;
-                }
-            }
+    // A hit repeats at every step from the start of its cycle, so take the first step of each residue after all the
+    // cycles have started
+    let earliest = cycles.iter().map(|cycle| cycle.start).max().unwrap_or(0).max(1);
+    let cyclic = residues
+        .iter()
+        .filter_map(|&(x, m)| match x >= earliest {
+            true => Some(x),
+            false => x.checked_add((earliest - x + m - 1) / m * m),
+        })
+        .min();
+
+    transient.into_iter().chain(cyclic).min()
+}
+
+// Returns the problems in the input, which must be a network (see parse_network). AAA and ZZZ must be defined in
+// part 1, and a node ending in 'A' in part 2.
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let graph = match parse_network(lines) {
+        Ok((_, graph)) => graph,
+        Err(problem) => return vec![problem],
+    };
+    let mut problems = Vec::new();
+    let starts: &[&str] = if cfg!(feature = "part2") { &[] } else { &["AAA", "ZZZ"] };
+    for start in starts {
+        if !graph.contains_key(*start) {
+            problems.push(Problem::line(0, format!("node {:?} is not defined", start)));
         }
-        stats.push(stat);
     }
+    if cfg!(feature = "part2") && !graph.keys().any(|node| node.ends_with('A')) {
+        problems.push(Problem::line(0, "no node ends in 'A'"));
+    }
+    problems
+}
 
-    let mut product: i64 = 1;
-    for stat in stats {
-        debug_assert!(stat.second == stat.first * 2);
-        debug_assert!(stat.first % 293 == 0);
-        product *= (stat.first / 293) as i64;
+// Returns random directions and a network in which each ghost runs around a loop with one end node, reaching it after
+// the same number of steps each time. The first ghost starts at AAA and ends at ZZZ. The size is the number of ghosts.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    const PRIMES: [usize; 10] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29];
+    const ALPHABET: &str = "BCDEFGHIJKLMNOPQRSTUVWXY";
+
+    let length = rng.between(3, 20) as usize;
+    let directions = rng.string(length, "LR");
+    let loop_lengths: Vec<usize> = (0..size).map(|_| directions.len() * rng.choose(&PRIMES)).collect();
+    let mut names = rng.names(loop_lengths.iter().sum::<usize>() + size, 3, ALPHABET, &[]);
+    let mut nodes = Vec::new();
+    for (ghost, &length) in loop_lengths.iter().enumerate() {
+        // Each loop is start -> n1 -> ... -> end -> n1
+        let mut path = names.split_off(names.len() - length);
+        let prefix = if ghost == 0 { "AA".to_string() } else { names.pop().unwrap()[..2].to_string() };
+        path[0] = format!("{}A", prefix);
+        path[length - 1] = format!("{}Z", if ghost == 0 { "ZZ" } else { &prefix });
+        for i in 0..length {
+            let next = if i == length - 1 { &path[1] } else { &path[i + 1] };
+            nodes.push(format!("{} = ({}, {})", path[i], next, next));
+        }
     }
-    product *= 293;
-    println!("Product: {}", product);
+    rng.shuffle(&mut nodes);
+    [vec![directions, String::new()], nodes].concat()
 }
 
-fn step<'a>(graph: &'a HashMap<String, (String, String)>, node_name: &String, direction: char) -> &'a String {
-    let node = graph.get(node_name).unwrap();
+// Returns the neighbor in the direction. The node is always defined, because the walks start at defined nodes and
+// parse_network checks that every neighbor is defined.
+fn step<'a>(graph: &'a Graph, node_name: &str, direction: char) -> &'a str {
+    let node = &graph[node_name];
     if direction == 'L' {
-        return &node.0;
+        &node.0
     } else {
-        return &node.1;
+        &node.1
     }
 }
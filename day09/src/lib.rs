@@ -0,0 +1,21 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::{self, Problem};
+
+/// Parses the sequences, one per line
+pub fn parse_sequences(lines: &[String]) -> Result<Vec<Vec<i64>>, Problem> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| parse_line(line).map_err(|message| Problem::line(i, message)))
+        .collect()
+}
+
+/// Parses a sequence of at least one number separated by spaces
+pub fn parse_line(line: &str) -> Result<Vec<i64>, String> {
+    let numbers = validate::numbers(line, ' ')?;
+    if numbers.is_empty() {
+        return Err("empty sequence".to_string());
+    }
+    Ok(numbers)
+}
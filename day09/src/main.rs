@@ -1,62 +1,134 @@
-use common::load;
+use common::bigint::BigInt;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{args, load, trace, warn};
+use day09::{parse_line, parse_sequences};
 
 fn main() {
+    generate::run(200, generate);
+    validate::check(validate);
     println!("Day 9, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
     let lines = load::lines().unwrap();
 
-    #[cfg(not(feature = "part2"))]
-    let mut nsum: i64 = 0;
-    #[cfg(feature = "part2")]
-    let mut psum: i64 = 0;
-
-    for line in lines {
-        let numbers: Vec<i64> = line.split_ascii_whitespace().map(|s| s.parse().unwrap()).collect();
-
-        let mut ends: Vec<i64> = Vec::new();
-        let mut starts: Vec<i64> = Vec::new();
-        let mut sequence = numbers.clone();
-        while !all_zeros(&sequence) {
-            starts.push(sequence[0]);
-            ends.push(*sequence.last().unwrap());
-            sequence = next_sequence(&sequence);
-        }
+    // Part 1 extrapolates forward from the last value, and part 2 backward from the first
+    let steps: i64 = args::value("--steps")
+        .map(|s| s.parse().unwrap_or_else(|_| panic!("Invalid --steps value: {}", s)))
+        .unwrap_or(1);
 
-        #[cfg(not(feature = "part2"))]
-        {
-            let n: i64 = ends.iter().sum();
-            nsum += n;
+    let sequences = validate::or_exit(parse_sequences(&lines));
+    let mut sum = BigInt::zero();
+    for (i, (line, numbers)) in lines.iter().zip(sequences).enumerate() {
+        let leading = leading_differences(&numbers);
+        if numbers.len() > 1 && leading.len() == numbers.len() {
+            warn!("Line {}: the differences never reach all zeros, so the last one is taken to be constant", i + 1);
         }
+        let position = if cfg!(feature = "part2") {
+            -steps
+        } else {
+            (numbers.len() as i64 - 1).checked_add(steps).expect("Too many steps")
+        };
+        let value = value_at(&leading, position);
+        trace!("{} -> {}", line, value);
+        sum = &sum + &value;
+    }
 
-        #[cfg(feature = "part2")]
-        {
-            let mut p: i64 = 0;
-            for s in starts.iter().rev() {
-                p = s - p;
-            }
-            psum += p;
-        }
+    if cfg!(feature = "part2") {
+        println!("psum: {}", sum);
+    } else {
+        println!("nsum: {}", sum);
     }
+}
 
-    #[cfg(not(feature = "part2"))]
-    println!("nsum: {}", nsum);
+// Returns the problems in the input. Each line is a sequence of at least one number.
+fn validate(lines: &[String]) -> Vec<Problem> {
+    validate::each_line(lines, |line| parse_line(line).map(|_| ()))
+}
 
-    #[cfg(feature = "part2")]
-    println!("psum: {}", psum);
+// Returns random sequences of 21 values of polynomials of degree up to 4. The size is the number of sequences.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            let coefficients: Vec<i64> = (0..=rng.between(0, 4)).map(|_| rng.between(-3, 3)).collect();
+            let values: Vec<String> = (0..21)
+                .map(|x: i64| coefficients.iter().rev().fold(0, |value, c| value * x + c).to_string())
+                .collect();
+            values.join(" ")
+        })
+        .collect()
 }
 
-fn all_zeros(sequence: &Vec<i64>) -> bool {
-    for i in sequence {
-        if *i != 0 {
-            return false;
+// Returns the first value of each row of the sequence's difference table, down to the last row that is not all zeros.
+// A row of one value has no differences, so it is the last row as if the next were all zeros. That makes a single
+// value a constant sequence.
+fn leading_differences(sequence: &[i64]) -> Vec<BigInt> {
+    let mut row: Vec<BigInt> = sequence.iter().map(|&n| BigInt::from(n)).collect();
+    let mut leading = Vec::new();
+    while row.iter().any(|n| !n.is_zero()) {
+        leading.push(row[0].clone());
+
+        // Replace the row with its differences
+        for i in 0..row.len() - 1 {
+            row[i] = &row[i + 1] - &row[i];
         }
+        row.pop();
+    }
+    leading
+}
+
+// Returns the value at a position in the sequence, where the first value is at 0. Positions after the last value or
+// before the first (negative) are extrapolated with Newton's forward difference formula: the value at n is the sum of
+// C(n, j) times the first value of the jth row of differences, where C(n, j) = n (n - 1) ... (n - j + 1) / j!.
+fn value_at(leading: &[BigInt], position: i64) -> BigInt {
+    let position = BigInt::from(position);
+    let mut value = BigInt::zero();
+    let mut binomial = BigInt::from(1);
+    for (j, difference) in leading.iter().enumerate() {
+        value = &value + &(&binomial * difference);
+        // C(n, j + 1) = C(n, j) * (n - j) / (j + 1), and the division is exact
+        let (quotient, remainder) = (&binomial * &(&position - &BigInt::from(j as i64))).div_rem_small(j as u32 + 1);
+        debug_assert!(remainder == 0);
+        binomial = quotient;
     }
-    true
+    value
 }
 
-fn next_sequence(sequence: &Vec<i64>) -> Vec<i64> {
-    let mut new_sequence = Vec::new();
-    for i in 1..sequence.len() {
-        new_sequence.push(sequence[i] - sequence[i - 1]);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Returns the values before the first and after the last value of the sequence
+    fn extrapolate(sequence: &[i64]) -> (String, String) {
+        let leading = leading_differences(sequence);
+        (value_at(&leading, -1).to_string(), value_at(&leading, sequence.len() as i64).to_string())
+    }
+
+    #[test]
+    fn examples() {
+        assert_eq!(extrapolate(&[0, 3, 6, 9, 12, 15]), ("-3".to_string(), "18".to_string()));
+        assert_eq!(extrapolate(&[1, 3, 6, 10, 15, 21]), ("0".to_string(), "28".to_string()));
+        assert_eq!(extrapolate(&[10, 13, 16, 21, 30, 45]), ("5".to_string(), "68".to_string()));
+    }
+
+    #[test]
+    fn single_value_is_constant() {
+        assert_eq!(leading_differences(&[5]), [BigInt::from(5)]);
+        assert_eq!(extrapolate(&[5]), ("5".to_string(), "5".to_string()));
+        assert_eq!(value_at(&leading_differences(&[5]), 1_000_000).to_string(), "5");
+        assert!(leading_differences(&[0, 0]).is_empty());
+    }
+
+    #[test]
+    fn differences_that_run_out() {
+        // The last row is [1], which is taken to be constant, so the sequence continues as n (n + 1) / 2 + 1
+        assert_eq!(leading_differences(&[1, 2, 4]).len(), 3);
+        assert_eq!(extrapolate(&[1, 2, 4]), ("1".to_string(), "7".to_string()));
+    }
+
+    #[test]
+    fn far_positions() {
+        // n² at n = 10^12 is 10^24, which does not fit in an i64
+        let leading = leading_differences(&[0, 1, 4, 9]);
+        assert_eq!(value_at(&leading, 1_000_000_000_000).to_string(), "1000000000000000000000000");
+        assert_eq!(value_at(&leading, -1_000_000_000_000).to_string(), "1000000000000000000000000");
     }
-    new_sequence
 }
@@ -1,6 +1,13 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{
+    args, debug, load, polygon,
+    svg::{Style, Svg},
+};
 
 fn main() {
+    generate::run(140, generate);
+    validate::check(validate);
     println!("Day 10, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
     let lines = load::lines().unwrap();
 
@@ -10,87 +17,75 @@ fn main() {
         grid.push(row);
     }
 
-    let mut occupied: Vec<Vec<bool>> = vec![vec![false; grid[0].len()]; grid.len()];
-
-    // Mark the starting point as occupied
+    // Follow the path until the start is reached again, collecting each point as a vertex of the loop
     let starting_point = find_start(&grid);
     let points = find_exits(starting_point, &grid);
-    grid[starting_point.1][starting_point.0] = type_from_exits(&points);
-
-    // Follow the path until the start is reached again, marking each point as occupied
-    occupied[starting_point.1][starting_point.0] = true;
-    let mut p = points[0].clone();
+    let mut vertices: Vec<(i64, i64)> = vec![(starting_point.0 as i64, starting_point.1 as i64)];
+    let mut p = points[0];
     while p.0 .0 != starting_point.0 || p.0 .1 != starting_point.1 {
-        occupied[p.0 .1][p.0 .0] = true;
+        vertices.push((p.0 .0 as i64, p.0 .1 as i64));
         p = next_point(p, &grid);
     }
 
-    // Clear all unoccupied points
-    clear_unoccupied_points(&mut grid, &occupied);
+    debug!("Start: {:?}, loop length: {}", starting_point, vertices.len());
 
-    // For each unoccupied point, find the number of times a ray in the direction (-1, -1) crosses a pipe wall
-    let mut number_of_inside_points = 0;
-    for y in 0..grid.len() {
-        for x in 0..grid[y].len() {
-            if !occupied[y][x] {
-                if count_crossings(x, y, &grid) % 2 == 1 {
-                    number_of_inside_points += 1;
-                }
-            }
-        }
+    // The inside points are the lattice points strictly inside the loop
+    let number_of_inside_points = polygon::interior_points(&vertices);
+
+    if let Some(path) = args::value("--svg") {
+        write_svg(&vertices, (grid[0].len(), grid.len()), &path);
     }
 
     println!("number of inside points: {}", number_of_inside_points);
 }
 
-fn clear_unoccupied_points(grid: &mut Vec<Vec<char>>, occupied: &Vec<Vec<bool>>) {
-    // Set all unoccupied points to '.'
-    for y in 0..grid.len() {
-        for x in 0..grid[y].len() {
-            if !occupied[y][x] {
-                grid[y][x] = '.';
-            }
-        }
-    }
+// Returns the problems in the input, which is a grid of pipes with one 'S'
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let mut problems = validate::grid(lines, "|-LJ7F.S");
+    problems.extend(validate::exactly_one(lines, 'S'));
+    problems
 }
 
-fn type_from_exits(exits: &Vec<((usize, usize), (isize, isize))>) -> char {
-    let d0 = exits[0].1;
-    let d1 = exits[1].1;
-    if d0.1 == -1 && d1.0 == 1 || d1.1 == -1 && d0.0 == 1 {
-        return 'L';
-    }
-    if d0.0 == 0 && d1.0 == 0 {
-        return '|';
-    }
-    if d0.1 == -1 && d1.0 == -1 || d1.1 == -1 && d0.0 == -1 {
-        return 'J';
-    }
-    if d0.1 == 1 && d1.0 == 1 || d1.1 == 1 && d0.0 == 1 {
-        return 'F';
-    }
-    if d0.1 == 0 && d1.1 == 0 {
-        return '-';
-    }
-    if d0.0 == -1 && d1.1 == 1 || d1.0 == -1 && d0.1 == 1 {
-        return '7';
+// Returns a grid with a random loop of pipes through 'S' among random pipes and ground. The size is the width and
+// height of the grid.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let size = size.max(2);
+    let mut grid: Vec<Vec<char>> = (0..size).map(|_| rng.string(size, "|-LJ7F......").chars().collect()).collect();
+    let vertices = generate::random_loop(rng, size - 1, size - 1);
+    for (i, &(x, y)) in vertices.iter().enumerate() {
+        let previous = vertices[(i + vertices.len() - 1) % vertices.len()];
+        let next = vertices[(i + 1) % vertices.len()];
+        let mut exits = [(previous.0 - x, previous.1 - y), (next.0 - x, next.1 - y)];
+        exits.sort();
+        grid[y as usize][x as usize] = match exits {
+            [(0, -1), (0, 1)] => '|',
+            [(-1, 0), (1, 0)] => '-',
+            [(0, -1), (1, 0)] => 'L',
+            [(-1, 0), (0, -1)] => 'J',
+            [(-1, 0), (0, 1)] => '7',
+            [(0, 1), (1, 0)] => 'F',
+            _ => unreachable!(),
+        };
+    }
+
+    // Put the start on the loop, and clear the tiles next to it that are not on the loop so that only the loop's pipes
+    // connect to it
+    let (x, y) = *rng.choose(&vertices);
+    grid[y as usize][x as usize] = 'S';
+    for (nx, ny) in [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)] {
+        if nx >= 0 && ny >= 0 && (nx as usize) < size && (ny as usize) < size && !vertices.contains(&(nx, ny)) {
+            grid[ny as usize][nx as usize] = '.';
+        }
     }
-    panic!("Unknown type");
+    grid.iter().map(|row| row.iter().collect()).collect()
 }
 
-fn count_crossings(x0: usize, y0: usize, grid: &Vec<Vec<char>>) -> i32 {
-    let mut number_of_crossings = 0;
-    let mut x = x0;
-    let mut y = y0;
-    while x > 0 && y > 0 {
-        x -= 1;
-        y -= 1;
-        let g = grid[y][x];
-        if g == '|' || g == '-' || g == 'F' || g == 'J' {
-            number_of_crossings += 1;
-        }
-    }
-    number_of_crossings
+// Draws the loop through the centers of its cells with the inside shaded
+fn write_svg(vertices: &[(i64, i64)], size: (usize, usize), path: &str) {
+    let mut svg = Svg::new((-0.5, -0.5), (size.0 as f64 - 0.5, size.1 as f64 - 0.5));
+    let points: Vec<(f64, f64)> = vertices.iter().map(|&(x, y)| (x as f64, y as f64)).collect();
+    svg.polygon(&points, &Style::new().fill_translucent("green", 0.4).stroke("black", 1.0));
+    svg.save(path).unwrap_or_else(|e| panic!("Could not write \"{}\": {}", path, e));
 }
 
 fn next_point(p: ((usize, usize), (isize, isize)), grid: &Vec<Vec<char>>) -> ((usize, usize), (isize, isize)) {
@@ -1,18 +1,41 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{debug, load};
 
 const EXPANSION: i64 = 1000000 - 1;
 
 fn main() {
+    generate::run(140, generate);
+    validate::check(validate);
     println!("Day 11, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
     let galaxy = load::lines().unwrap();
 
     let (xr, xc) = expand(&galaxy);
     let stars = find_stars(&galaxy);
+    debug!("Empty rows: {:?}, empty columns: {:?}, stars: {}", xr, xc, stars.len());
     let distances = find_distances(&stars, &xr, &xc);
 
     println!("Sum of distances: {}", distances.iter().sum::<i64>());
 }
 
+// Returns the problems in the input, which is a grid of '.' and '#'
+fn validate(lines: &[String]) -> Vec<Problem> {
+    validate::grid(lines, ".#")
+}
+
+// Returns a random image with galaxies and some empty rows and columns. The size is the width and height.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let empty_rows: Vec<bool> = (0..size).map(|_| rng.chance(0.05)).collect();
+    let empty_columns: Vec<bool> = (0..size).map(|_| rng.chance(0.05)).collect();
+    (0..size)
+        .map(|y| {
+            (0..size)
+                .map(|x| if !empty_rows[y] && !empty_columns[x] && rng.chance(0.02) { '#' } else { '.' })
+                .collect()
+        })
+        .collect()
+}
+
 fn find_distances(stars: &Vec<(usize, usize)>, xr: &Vec<usize>, xc: &Vec<usize>) -> Vec<i64> {
     let mut distances: Vec<i64> = Vec::new();
     for i in 0..stars.len() - 1 {
@@ -0,0 +1,78 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::Problem;
+
+/// A spring record converted to bit masks
+pub struct Record {
+    /// Total length of the spring record
+    pub size: usize,
+    /// Bitmask with 1s at '#' positions
+    pub template: u128,
+    /// Bitmask with 0s at '?' positions
+    pub mask: u128,
+}
+
+/// Parses the records and their groups, one per line. The records are unfolded in part 2.
+pub fn parse_records(lines: &[String]) -> Result<Vec<(Record, Vec<i32>)>, Problem> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| parse_line(line).map_err(|message| Problem::line(i, message)))
+        .collect()
+}
+
+/// Parses a record and its groups such as "???.### 1,1,3". The groups must fit in the record, and the record,
+/// unfolded in part 2, must fit in the 128-bit masks.
+pub fn parse_line(line: &str) -> Result<(Record, Vec<i32>), String> {
+    let (springs, groups) = line.split_once(' ').ok_or("expected \"<record> <groups>\"")?;
+    if let Some(c) = springs.chars().find(|&c| !".#?".contains(c)) {
+        return Err(format!("unexpected character {:?}", c));
+    }
+    let fold_length = springs.len();
+    let size = if cfg!(feature = "part2") { fold_length * 5 + 4 } else { fold_length };
+    if size > 128 {
+        return Err(format!("the record is {} springs, more than 128", size));
+    }
+    let numbers: Vec<i32> = groups
+        .split(',')
+        .map(|s| match s.parse() {
+            Ok(n) if n > 0 => Ok(n),
+            _ => Err(format!("{:?} is not a positive group size", s)),
+        })
+        .collect::<Result<_, _>>()?;
+    if numbers.iter().map(|&n| n as usize + 1).sum::<usize>() - 1 > fold_length {
+        return Err("the groups do not fit in the record".to_string());
+    }
+    let (template, mask) = parse_record(springs);
+
+    if cfg!(feature = "part2") {
+        let mut unfolded_template = 0;
+        let mut unfolded_mask = !0;
+        let mut unfolded_numbers: Vec<i32> = vec![];
+        for _ in 0..5 {
+            unfolded_template = (unfolded_template << (fold_length + 1)) | template;
+            unfolded_mask = (unfolded_mask << (fold_length + 1)) | (mask & ((1 << fold_length) - 1));
+            unfolded_numbers.extend(&numbers);
+        }
+        let record = Record {
+            size,
+            template: unfolded_template,
+            mask: unfolded_mask,
+        };
+        Ok((record, unfolded_numbers))
+    } else {
+        let record = Record { size, template, mask };
+        Ok((record, numbers))
+    }
+}
+
+/// Returns (template, mask)
+/// where template has 1's for positions that are '#' and mask has 0s for positions that are '?'
+pub fn parse_record(record: &str) -> (u128, u128) {
+    let (template, mask) = record.chars().fold((0, 0), |(mut template, mut mask), c| {
+        template = (template << 1) | if c == '#' { 1 } else { 0 };
+        mask = (mask << 1) | if c == '?' { 1 } else { 0 };
+        (template, mask)
+    });
+    (template, !mask)
+}
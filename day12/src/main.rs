@@ -1,24 +1,115 @@
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{cancel, debug, load, memo::Memo, progress::Progress, trace};
+use day12::{parse_records, Record};
 use std::collections::HashMap;
 
-use common::load;
-
 fn main() {
+    cancel::start();
+    generate::run_with_answer(1000, generate, answer);
+    validate::check(validate);
     println!("Day 12, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
     let lines = load::lines().unwrap();
+    let records = validate::or_exit(parse_records(&lines));
 
+    let mut progress = Progress::new("Records", Some(lines.len() as u64));
     let mut sum: i64 = 0;
-    for line in lines {
-        let (size, template, mask, groups) = parse_line(&line);
-        let space = size - (groups.iter().sum::<i32>() as usize + groups.len() - 1);
-        let mut cache: HashMap<(usize, usize, usize), i64> = HashMap::new();
-        let count = number_of_permutations(&mut cache, size, template, mask, 0, 0, &groups, space);
-        ("{}: {} {:b} {:b} {:?} {}", line, size, template, mask, groups, count);
+    for (line, (record, groups)) in lines.iter().zip(records) {
+        cancel::check().unwrap_or_else(cancel::exit);
+        let count = count_arrangements(&record, &groups);
+        debug!("{}: {} {:b} {:b} {:?} {}", line, record.size, record.template, record.mask, groups, count);
         sum += count;
+        progress.tick();
     }
+    progress.finish();
 
     println!("Sum: {}", sum);
 }
 
+// Returns the problems in the input. Each line is a record of '.', '#', and '?', and a list of group sizes. The
+// record, unfolded in part 2, must fit in the 128-bit masks.
+fn validate(lines: &[String]) -> Vec<Problem> {
+    parse_records(lines).err().into_iter().collect()
+}
+
+// Returns random records, each made from a random arrangement with some of the springs replaced by '?', so there is
+// at least one arrangement. The records are short enough to be unfolded in part 2. The size is the number of records.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size)
+        .map(|_| {
+            let length = rng.between(4, 20) as usize;
+            let mut springs = String::new();
+            let mut groups = Vec::new();
+            while springs.len() < length {
+                if rng.chance(0.5) || springs.ends_with('#') {
+                    springs.push('.');
+                } else {
+                    let group = rng.between(1, 4).min((length - springs.len()) as i64);
+                    springs.push_str(&"#".repeat(group as usize));
+                    groups.push(group.to_string());
+                }
+            }
+            if groups.is_empty() {
+                springs.replace_range(length - 1.., "#");
+                groups.push("1".to_string());
+            }
+            let record: String = springs.chars().map(|c| if rng.chance(0.5) { '?' } else { c }).collect();
+            format!("{} {}", record, groups.join(","))
+        })
+        .collect()
+}
+
+// Returns the number of arrangements of each generated record, one per line, and then their sum as main prints it.
+// The records are unfolded in part 2.
+fn answer(lines: &[String]) -> Vec<String> {
+    let counts: Vec<i64> = lines
+        .iter()
+        .map(|line| {
+            let (springs, groups) = line.split_once(' ').unwrap();
+            let groups: Vec<i32> = groups.split(',').map(|g| g.parse().unwrap()).collect();
+            if cfg!(feature = "part2") {
+                count_arrangements_by_scanning(&[springs; 5].join("?"), &groups.repeat(5))
+            } else {
+                count_arrangements_by_scanning(springs, &groups)
+            }
+        })
+        .collect();
+    let sum: i64 = counts.iter().sum();
+    counts.iter().map(|count| count.to_string()).chain([format!("Sum: {}", sum)]).collect()
+}
+
+// Returns the number of arrangements of the groups that match the springs. It scans the springs one at a time,
+// counting the partial arrangements that end in each state: the number of groups completed and the length of the
+// group in progress.
+fn count_arrangements_by_scanning(springs: &str, groups: &[i32]) -> i64 {
+    let mut states: HashMap<(usize, i32), i64> = HashMap::from([((0, 0), 1)]);
+    // A trailing '.' completes the last group
+    for c in springs.chars().chain(['.']) {
+        let mut next = HashMap::new();
+        for (&(done, length), &count) in &states {
+            if c != '.' && done < groups.len() && length < groups[done] {
+                *next.entry((done, length + 1)).or_insert(0) += count;
+            }
+            if c != '#' && length == 0 {
+                *next.entry((done, 0)).or_insert(0) += count;
+            } else if c != '#' && length == groups[done] {
+                *next.entry((done + 1, 0)).or_insert(0) += count;
+            }
+        }
+        states = next;
+    }
+    states.get(&(groups.len(), 0)).copied().unwrap_or(0)
+}
+
+// Returns the number of arrangements of the groups that match the record
+fn count_arrangements(record: &Record, groups: &[i32]) -> i64 {
+    let space = record.size - (groups.iter().sum::<i32>() as usize + groups.len() - 1);
+    let mut memo = Memo::new();
+    let count = number_of_permutations(&mut memo, record, 0, 0, groups, space);
+    trace!("{:?}", memo.stats());
+    count
+}
+
 fn insert_ones(x: u128, n: usize) -> u128 {
     x << n | (1 << n) - 1
 }
@@ -40,29 +131,36 @@ fn matches_template(x: u128, template: u128, mask: u128) -> bool {
 /// - Recursive case: places group + 1 separator zero, prunes if partial wip
 ///   doesn't match template, then recurses with remaining groups and reduced space
 ///
+/// Results are memoized by (wip_size, number of remaining groups, remaining space) because the wip placed so far has
+/// already been matched against the template.
+///
 /// Parameters:
-/// - size: total length of the spring record
-/// - template: bitmask with 1s at '#' positions
-/// - mask: bitmask with 0s at '?' positions
+/// - record: the spring record to match
 /// - wip: work-in-progress bitmask built left-to-right
 /// - wip_size: number of bits placed in wip so far
 /// - groups: remaining group sizes to place
 /// - space: remaining space to distribute
 fn number_of_permutations(
-    cache: &mut HashMap<(usize, usize, usize), i64>,
-    size: usize,
-    template: u128,
-    mask: u128,
+    memo: &mut Memo<(usize, usize, usize), i64>,
+    record: &Record,
     wip: u128,
     wip_size: usize,
     remaining_groups: &[i32],
     remaining_space: usize,
 ) -> i64 {
-    // Check cache
-    if let Some(&cached_count) = cache.get(&(wip_size, remaining_groups.len(), remaining_space)) {
-        return cached_count;
-    }
+    memo.get_or_insert_with((wip_size, remaining_groups.len(), remaining_space), |memo| {
+        count_permutations(memo, record, wip, wip_size, remaining_groups, remaining_space)
+    })
+}
 
+fn count_permutations(
+    memo: &mut Memo<(usize, usize, usize), i64>,
+    record: &Record,
+    wip: u128,
+    wip_size: usize,
+    remaining_groups: &[i32],
+    remaining_space: usize,
+) -> i64 {
     let g = remaining_groups[0] as usize;
     let mut count = 0;
 
@@ -73,13 +171,11 @@ fn number_of_permutations(
             // If this is not the last group, force a separator zero.
             let new_wip = insert_zeros(new_wip, 1);
             let new_wip_size = wip_size + i + g + 1;
-            let shift = size - new_wip_size;
-            if matches_template(new_wip, template >> shift, mask >> shift) {
+            let shift = record.size - new_wip_size;
+            if matches_template(new_wip, record.template >> shift, record.mask >> shift) {
                 count += number_of_permutations(
-                    cache,
-                    size,
-                    template,
-                    mask,
+                    memo,
+                    record,
                     new_wip,
                     new_wip_size,
                     &remaining_groups[1..],
@@ -89,51 +185,90 @@ fn number_of_permutations(
         } else {
             // Last group: complete with trailing zeros and check full match.
             let new_wip = insert_zeros(new_wip, remaining_space - i);
-            if matches_template(new_wip, template, mask) {
+            if matches_template(new_wip, record.template, record.mask) {
                 count += 1;
             }
         }
     }
 
-    // Cache result
-    cache.insert((wip_size, remaining_groups.len(), remaining_space), count);
-
     count
 }
 
-fn parse_line(line: &str) -> (usize, u128, u128, Vec<i32>) {
-    let parts: Vec<&str> = line.split_whitespace().collect();
-    let (template, mask) = parse_record(parts[0]);
-    let numbers: Vec<i32> = parts[1]
-        .split(',')
-        .map(|s| s.parse().expect("Failed to parse number"))
-        .collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::verify;
+    use day12::{parse_line, parse_record};
 
-    if cfg!(feature = "part2") {
-        let fold_length = parts[0].len();
-        assert!(fold_length * 5 <= 128);
-        let unfolded_size = fold_length * 5 + 4;
-        let mut unfolded_template = 0;
-        let mut unfolded_mask = !0;
-        let mut unfolded_numbers: Vec<i32> = vec![];
-        for _ in 0..5 {
-            unfolded_template = (unfolded_template << (fold_length + 1)) | template;
-            unfolded_mask = (unfolded_mask << (fold_length + 1)) | (mask & ((1 << fold_length) - 1));
-            unfolded_numbers.extend(&numbers);
+    #[test]
+    fn arrangements() {
+        verify::property(100, 16, check_arrangements);
+    }
+
+    #[test]
+    fn generated_answers() {
+        verify::property(20, 50, check_answer);
+    }
+
+    // Checks count_arrangements against the answer written for a generated input of `size` records
+    fn check_answer(rng: &mut Rng, size: usize) -> Result<(), String> {
+        let lines = generate(rng, size);
+        for (line, expected) in lines.iter().zip(answer(&lines)) {
+            let (record, groups) = parse_line(line)?;
+            let actual = count_arrangements(&record, &groups);
+            if actual.to_string() != expected {
+                return Err(format!("{}: {} arrangements, expected {}", line, actual, expected));
+            }
         }
-        return (unfolded_size, unfolded_template, unfolded_mask, unfolded_numbers);
-    } else {
-        return (parts[0].len(), template, mask, numbers);
+        Ok(())
     }
-}
 
-/// Returns (template, mask)
-/// where template has 1's for positions that are '#' and mask has 0s for positions that are '?'
-fn parse_record(record: &str) -> (u128, u128) {
-    let (template, mask) = record.chars().fold((0, 0), |(mut template, mut mask), c| {
-        template = (template << 1) | if c == '#' { 1 } else { 0 };
-        mask = (mask << 1) | if c == '?' { 1 } else { 0 };
-        (template, mask)
-    });
-    (template, !mask)
+    // Checks count_arrangements against enumeration for a random record of up to `size` springs and random groups that
+    // fit in it. The record is not unfolded.
+    fn check_arrangements(rng: &mut Rng, size: usize) -> Result<(), String> {
+        let length = rng.between(1, size as i64) as usize;
+        let springs = rng.string(length, ".#??");
+        let mut groups = Vec::new();
+        let mut used = 0;
+        while groups.is_empty() || (used < length && rng.chance(0.5)) {
+            let group = rng.between(1, (length - used).clamp(1, 4) as i64) as usize;
+            if used + group > length {
+                break;
+            }
+            groups.push(group as i32);
+            used += group + 1;
+        }
+        let (template, mask) = parse_record(&springs);
+        let record = Record {
+            size: length,
+            template,
+            mask,
+        };
+        let expected = count_arrangements_by_enumeration(&springs, &groups);
+        let actual = count_arrangements(&record, &groups);
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(format!("{} {:?}: {} arrangements, expected {}", springs, groups, actual, expected))
+        }
+    }
+
+    // Returns the number of arrangements of the groups that match the springs by trying every replacement of the '?'s
+    fn count_arrangements_by_enumeration(springs: &str, groups: &[i32]) -> i64 {
+        let unknowns: Vec<usize> = springs.char_indices().filter(|&(_, c)| c == '?').map(|(i, _)| i).collect();
+        let mut count = 0;
+        for replacement in 0..1u32 << unknowns.len() {
+            let mut candidate: Vec<char> = springs.chars().collect();
+            for (bit, &i) in unknowns.iter().enumerate() {
+                candidate[i] = if replacement & (1 << bit) != 0 { '#' } else { '.' };
+            }
+            let candidate: String = candidate.into_iter().collect();
+            let candidate_groups: Vec<i32> =
+                candidate.split('.').filter(|g| !g.is_empty()).map(|g| g.len() as i32).collect();
+            if candidate_groups == groups {
+                count += 1;
+            }
+        }
+        count
+    }
 }
@@ -1,14 +1,20 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{debug, load};
 
 fn main() {
+    generate::run(100, generate);
+    validate::check(validate);
     println!("Day 13, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
     let lines = load::lines().unwrap();
     let maps = load_maps(&lines);
     let mut sum: i32 = 0;
     for m in maps {
         if let Some(col) = find_vertical_mirror(&m) {
+            debug!("Vertical mirror after column {}", col);
             sum += col;
         } else if let Some(row) = find_horizontal_mirror(&m) {
+            debug!("Horizontal mirror after row {}", row);
             sum += 100 * row;
         } else {
             panic!("Error: no split found");
@@ -17,6 +23,52 @@ fn main() {
     println!("Sum: {}", sum);
 }
 
+// Returns the problems in the input, which is grids of '.' and '#' separated by blank lines
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut start = 0;
+    for block in lines.split(|line| line.is_empty()) {
+        if !block.is_empty() {
+            problems.extend(validate::grid(block, ".#").into_iter().map(|problem| Problem {
+                line: problem.line + start,
+                ..problem
+            }));
+        }
+        start += block.len() + 1;
+    }
+    problems
+}
+
+// Returns random patterns, each with a reflection across a column and a reflection across a row that has one smudge.
+// The smudge is outside the columns that are reflected, so the column's reflection is exact. The size is the number
+// of patterns.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for _ in 0..size {
+        let width = rng.between(5, 17) as usize;
+        let height = rng.between(2, 17) as usize;
+        let column = rng.between(1, (width as i64 - 1) / 2) as usize;
+        let row = rng.between(1, height as i64 - 1) as usize;
+        let mut pattern: Vec<Vec<char>> = (0..height).map(|_| rng.string(width, ".#").chars().collect()).collect();
+        for line in pattern.iter_mut() {
+            for j in 0..column {
+                line[column + j] = line[column - 1 - j];
+            }
+        }
+        for i in 0..row.min(height - row) {
+            pattern[row + i] = pattern[row - 1 - i].clone();
+        }
+        let span = row.min(height - row);
+        let i = rng.between((row - span) as i64, (row + span - 1) as i64) as usize;
+        let j = rng.between(2 * column as i64, width as i64 - 1) as usize;
+        pattern[i][j] = if pattern[i][j] == '#' { '.' } else { '#' };
+        lines.extend(pattern.iter().map(|line| line.iter().collect::<String>()));
+        lines.push(String::new());
+    }
+    lines.pop();
+    lines
+}
+
 fn load_maps(lines: &[String]) -> Vec<Vec<Vec<char>>> {
     let mut maps: Vec<Vec<Vec<char>>> = vec![];
     let mut map: Vec<Vec<char>> = vec![];
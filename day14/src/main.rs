@@ -1,39 +1,75 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{
+    cycle,
+    frames::Recorder,
+    info, load,
+    render::{self, Color, Overlay},
+};
 
-const CYCLES: i64 = 1000000;
+const CYCLES: usize = 1000000000;
 
 fn main() {
+    generate::run(100, generate);
+    validate::check(validate);
     println!("Day 14, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
     let lines = load::lines().unwrap();
     let mut map: Vec<Vec<char>> = vec![];
     for line in lines {
         map.push(line.chars().collect());
     }
-    //    print_map(&map);
+
+    let mut recorder = Recorder::from_args();
+    record(&mut recorder, &map);
 
     if cfg!(feature = "part2") {
-        let mut previous = map.clone();
-        for i in 0..CYCLES {
-            tip_north(&mut map);
-            tip_west(&mut map);
-            tip_south(&mut map);
-            tip_east(&mut map);
-            if i % (CYCLES / 10) == 0 {
-                println!("Cycles: {:.0}%", (i as f64 / CYCLES as f64 * 100.0).round());
-            }
-            if previous == map {
-                println!("Stable after {} cycles", i);
-                break;
-            }
-            previous = map.clone();
-        }
+        // The rocks settle into a repeating pattern long before the last cycle, so find it and skip ahead.
+        let history = cycle::detect(map, |map| {
+            let mut map = map.clone();
+            spin(&mut map, &mut recorder);
+            map
+        });
+        info!("Repeats every {} cycles after {} cycles", history.cycle.period, history.cycle.start);
+        map = history.nth(CYCLES).clone();
     } else {
         tip_north(&mut map);
+        record(&mut recorder, &map);
+    }
+
+    if render::enabled() {
+        render::print(&map, &[Overlay::from_grid(&map, |&c| c == 'O', Color::Cyan).with_glyph('O')]);
     }
 
     println!("Load: {}", map_load(&map));
 }
 
+// Returns the problems in the input, which is a grid of 'O', '#', and '.'
+fn validate(lines: &[String]) -> Vec<Problem> {
+    validate::grid(lines, "O#.")
+}
+
+// Returns a random platform of rounded and cube-shaped rocks. The size is the width and height.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size).map(|_| rng.string(size, "OO##..........")).collect()
+}
+
+fn spin(map: &mut Vec<Vec<char>>, recorder: &mut Option<Recorder>) {
+    tip_north(map);
+    record(recorder, map);
+    tip_west(map);
+    record(recorder, map);
+    tip_south(map);
+    record(recorder, map);
+    tip_east(map);
+    record(recorder, map);
+}
+
+fn record(recorder: &mut Option<Recorder>, map: &[Vec<char>]) {
+    if let Some(recorder) = recorder {
+        recorder.record(map, &[]).expect("Failed to write frame");
+    }
+}
+
 fn tip_north(map: &mut Vec<Vec<char>>) {
     let rows = map.len();
     let cols = map[0].len();
@@ -0,0 +1,43 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::Problem;
+
+/// A step of the initialization sequence, with the label of the lens it operates on
+#[derive(Debug)]
+pub enum Step {
+    /// Puts a lens with a focal length in its box, replacing the lens with the same label if there is one
+    Insert(String, i64),
+    /// Takes the lens with the label out of its box
+    Remove(String),
+}
+
+/// Parses the steps, which are separated by ',' on each line
+pub fn parse_steps(lines: &[String]) -> Result<Vec<Step>, Problem> {
+    let mut steps = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let mut column = 0;
+        for step in line.split(',') {
+            steps.push(parse_step(step.trim()).map_err(|message| Problem::at(i, column, message))?);
+            column += step.chars().count() + 1;
+        }
+    }
+    Ok(steps)
+}
+
+/// Parses a step: a label of lowercase letters followed by "=<focal length>", such as "rn=1", or by '-', such as "cm-"
+pub fn parse_step(step: &str) -> Result<Step, String> {
+    let label_length = step.chars().take_while(|c| c.is_ascii_lowercase()).count();
+    let (label, operation) = step.split_at(label_length);
+    if label.is_empty() {
+        return Err(format!("the step {:?} has no label", step));
+    }
+    if operation == "-" {
+        return Ok(Step::Remove(label.to_string()));
+    }
+    let f = operation
+        .strip_prefix('=')
+        .filter(|f| !f.is_empty() && f.bytes().all(|b| b.is_ascii_digit()))
+        .ok_or_else(|| format!("invalid step {:?}", step))?;
+    let f = f.parse().map_err(|_| format!("the focal length in {:?} is too large", step))?;
+    Ok(Step::Insert(label.to_string(), f))
+}
@@ -1,22 +1,30 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{load, trace};
 #[cfg(feature = "part2")]
-use regex::Regex;
+use day15::{parse_steps, Step};
 
 fn main() {
+    generate::run(4000, generate);
+    validate::check(validate);
     println!("Day 15, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
     #[cfg(not(feature = "part2"))]
     {
         let steps = load::comma_separated_values().unwrap();
-        let sum: u64 = steps.iter().map(|s| hash(s)).sum();
+        let sum: u64 = steps
+            .iter()
+            .map(|s| {
+                let h = hash(s);
+                trace!("{}: {}", s, h);
+                h
+            })
+            .sum();
         println!("Sum: {}", sum);
     }
 
     #[cfg(feature = "part2")]
     {
-        let steps = load::comma_separated_values().unwrap();
-
-        let re_assign = Regex::new(r"^([a-z]+)=(\d+)$").unwrap();
-        let re_remove = Regex::new(r"^([a-z]+)-$").unwrap();
+        let steps = validate::or_exit(parse_steps(&load::lines().unwrap()));
 
         #[derive(Debug)]
         struct Lens {
@@ -28,32 +36,32 @@ fn main() {
         for _ in 0..256 {
             boxes.push(Vec::new());
         }
-        for s in steps {
-            if let Some(captured) = re_assign.captures(&s) {
-                let symbol = captured.get(1).unwrap().as_str();
-                let f: i64 = captured.get(2).unwrap().as_str().parse().unwrap();
-                let i = hash(&symbol) as usize;
-                let mut found = false;
-                for lens in &mut boxes[i] {
-                    if lens.symbol == symbol {
-                        lens.f = f;
-                        found = true;
-                        break;
+        for step in &steps {
+            match step {
+                Step::Insert(symbol, f) => {
+                    let i = hash(symbol) as usize;
+                    let mut found = false;
+                    for lens in &mut boxes[i] {
+                        if lens.symbol == *symbol {
+                            lens.f = *f;
+                            found = true;
+                            break;
+                        }
+                    }
+                    if !found {
+                        boxes[i].push(Lens {
+                            symbol: symbol.to_string(),
+                            f: *f,
+                        });
                     }
                 }
-                if !found {
-                    boxes[i].push(Lens {
-                        symbol: symbol.to_string(),
-                        f,
-                    });
+                Step::Remove(symbol) => {
+                    let i = hash(symbol) as usize;
+                    boxes[i].retain(|lens| lens.symbol != *symbol);
                 }
-            } else if let Some(captured) = re_remove.captures(&s) {
-                let symbol = captured.get(1).unwrap().as_str();
-                let i = hash(&symbol) as usize;
-                boxes[i].retain(|lens| lens.symbol != symbol);
-            } else {
-                panic!("No match for: {}", s);
             }
+            let filled: Vec<_> = boxes.iter().enumerate().filter(|(_, b)| !b.is_empty()).collect();
+            trace!("After {:?}: {:?}", step, filled);
         }
         // Sum the focusing power of lenses in boxes that are not empty
         let sum: i64 = boxes.iter().enumerate().fold(0, |a0, (b, lenses)| {
@@ -66,6 +74,47 @@ fn main() {
     }
 }
 
+// Returns the problems in the input, which is steps such as "rn=1" and "cm-" separated by ','
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let mut column = 0;
+        for step in line.split(',') {
+            let label_length = step.chars().take_while(|c| c.is_ascii_lowercase()).count();
+            let operation = &step[label_length..];
+            let valid = label_length > 0
+                && (operation == "-"
+                    || operation.strip_prefix('=').is_some_and(|f| f.len() == 1 && f.as_bytes()[0].is_ascii_digit()));
+            if !valid {
+                problems.push(Problem::at(i, column, format!("invalid step {:?}", step)));
+            }
+            column += step.len() + 1;
+        }
+    }
+    problems
+}
+
+// Returns a random initialization sequence using a pool of labels. The size is the number of steps.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let labels: Vec<String> = (0..size.div_ceil(4))
+        .map(|_| {
+            let length = rng.between(2, 6) as usize;
+            rng.string(length, "abcdefghijklmnopqrstuvwxyz")
+        })
+        .collect();
+    let steps: Vec<String> = (0..size)
+        .map(|_| {
+            let label = rng.choose(&labels);
+            if rng.chance(0.3) {
+                format!("{}-", label)
+            } else {
+                format!("{}={}", label, rng.between(1, 9))
+            }
+        })
+        .collect();
+    vec![steps.join(",")]
+}
+
 fn hash(s: &str) -> u64 {
     s.chars().fold(0, |sum, c| ((sum + c as u64) * 17) & 0xff)
 }
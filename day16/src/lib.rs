@@ -0,0 +1,14 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::{self, Problem};
+
+/// The cells of the contraption: empty space, mirrors, and splitters
+pub const CELLS: &str = ".|-/\\";
+
+/// Parses the contraption, which is a grid of cells
+pub fn parse_contraption(lines: &[String]) -> Result<Vec<Vec<char>>, Problem> {
+    match validate::grid(lines, CELLS).into_iter().next() {
+        Some(problem) => Err(problem),
+        None => Ok(lines.iter().map(|line| line.chars().collect()).collect()),
+    }
+}
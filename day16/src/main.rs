@@ -1,4 +1,15 @@
-use common::load;
+#[cfg(feature = "part2")]
+use common::debug;
+#[cfg(not(feature = "part2"))]
+use common::render;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{
+    frames::Recorder,
+    load,
+    render::{Color, Overlay},
+};
+use day16::{parse_contraption, CELLS};
 
 #[derive(Debug, Clone, Copy)]
 enum Direction {
@@ -15,9 +26,11 @@ struct Branch {
 }
 
 fn main() {
+    generate::run(110, generate);
+    validate::check(validate);
     println!("Day 16, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
 
-    let map = load::map().unwrap();
+    let map = validate::or_exit(parse_contraption(&load::lines().unwrap()));
 
     #[cfg(not(feature = "part2"))]
     {
@@ -26,7 +39,12 @@ fn main() {
             x: 0,
             y: 0,
         };
-        let energize = energize(map, start);
+        let mut recorder = Recorder::from_args();
+        let energized = energized(map.clone(), start, recorder.as_mut());
+        if render::enabled() {
+            render::print(&map, &[Overlay::from_grid(&energized, |&e| e != 0, Color::Yellow)]);
+        }
+        let energize: i32 = energized.iter().flatten().sum();
         println!("Energized cells: {}", energize);
     }
     #[cfg(feature = "part2")]
@@ -39,6 +57,7 @@ fn main() {
                 y,
             };
             let energize = energize(map.clone(), start);
+            debug!("{:?}: {}", start, energize);
             if energize > max {
                 max = energize;
             }
@@ -51,6 +70,7 @@ fn main() {
                 y: map.len() - 1,
             };
             let energize = energize(map.clone(), start);
+            debug!("{:?}: {}", start, energize);
             if energize > max {
                 max = energize;
             }
@@ -63,6 +83,7 @@ fn main() {
                 y,
             };
             let energize = energize(map.clone(), start);
+            debug!("{:?}: {}", start, energize);
             if energize > max {
                 max = energize;
             }
@@ -75,6 +96,7 @@ fn main() {
                 y: 0,
             };
             let energize = energize(map.clone(), start);
+            debug!("{:?}: {}", start, energize);
             if energize > max {
                 max = energize;
             }
@@ -83,7 +105,27 @@ fn main() {
     }
 }
 
-fn energize(mut map: Vec<Vec<char>>, start: Branch) -> i32 {
+// Returns the problems in the input, which is a grid of '.', mirrors, and splitters
+fn validate(lines: &[String]) -> Vec<Problem> {
+    validate::grid(lines, CELLS)
+}
+
+// Returns a random contraption of mirrors and splitters. The size is the width and height.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size)
+        .map(|_| (0..size).map(|_| if rng.chance(0.1) { *rng.choose(&['|', '-', '/', '\\']) } else { '.' }).collect())
+        .collect()
+}
+
+#[cfg(feature = "part2")]
+fn energize(map: Vec<Vec<char>>, start: Branch) -> i32 {
+    // Return the number of energized cells
+    energized(map, start, None).iter().flatten().sum()
+}
+
+// Returns a grid with 1 in each cell that is energized and 0 otherwise. If a recorder is given, a frame is recorded
+// after each branch is followed.
+fn energized(mut map: Vec<Vec<char>>, start: Branch, mut recorder: Option<&mut Recorder>) -> Vec<Vec<i32>> {
     let mut energized: Vec<Vec<i32>> = vec![vec![0; map[0].len()]; map.len()];
     let mut branches: Vec<Branch> = Vec::new();
     branches.push(start);
@@ -102,9 +144,13 @@ fn energize(mut map: Vec<Vec<char>>, start: Branch) -> i32 {
             Direction::Left => move_left(&mut map, x, y, &mut branches, &mut energized),
             Direction::Down => move_down(&mut map, x, y, &mut branches, &mut energized),
         }
+
+        if let Some(recorder) = recorder.as_deref_mut() {
+            let beams = Overlay::from_grid(&energized, |&e| e != 0, Color::Yellow);
+            recorder.record(&map, &[beams]).expect("Failed to write frame");
+        }
     }
-    // Return the number of energized cells
-    energized.iter().flatten().sum()
+    energized
 }
 
 fn move_right(map: &mut Vec<Vec<char>>, mut x: usize, y: usize, branches: &mut Vec<Branch>, energized: &mut Vec<Vec<i32>>) {
@@ -133,7 +179,7 @@ fn move_right(map: &mut Vec<Vec<char>>, mut x: usize, y: usize, branches: &mut V
         '.' => {} // Going off the edge, so we don't continue in any direction
         '-' => {} // Going off the edge, so we don't continue in any direction
 
-        _ => panic!("Unexpected character: {}", map[y][x]),
+        _ => unreachable!(),
     }
 }
 
@@ -163,7 +209,7 @@ fn move_up(map: &mut Vec<Vec<char>>, x: usize, mut y: usize, branches: &mut Vec<
         '.' => {} // Going off the edge, so we don't continue in any direction
         '|' => {} // Going off the edge, so we don't continue in any direction
 
-        _ => panic!("Unexpected character: {}", map[y][x]),
+        _ => unreachable!(),
     }
 }
 
@@ -193,7 +239,7 @@ fn move_left(map: &mut Vec<Vec<char>>, mut x: usize, y: usize, branches: &mut Ve
         '.' => {} // Going off the edge, so we don't continue in any direction
         '-' => {} // Going off the edge, so we don't continue in any direction
 
-        _ => panic!("Unexpected character: {}", map[y][x]),
+        _ => unreachable!(),
     }
 }
 
@@ -223,7 +269,7 @@ fn move_down(map: &mut Vec<Vec<char>>, x: usize, mut y: usize, branches: &mut Ve
         '.' => {} // Going off the edge, so we don't continue in any direction
         '|' => {} // Going off the edge, so we don't continue in any direction
 
-        _ => panic!("Unexpected character: {}", map[y][x]),
+        _ => unreachable!(),
     }
 }
 
@@ -266,12 +312,3 @@ fn branch_right(x: usize, y: usize, edge: usize, branches: &mut Vec<Branch>) {
         })
     }
 }
-
-fn _print_map(map: &Vec<Vec<char>>) {
-    for row in map {
-        for c in row {
-            print!("{}", c);
-        }
-        println!();
-    }
-}
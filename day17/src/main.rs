@@ -1,4 +1,9 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{
+    cancel::{self, Cancelled},
+    debug, load, trace,
+};
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 
@@ -6,6 +11,9 @@ const MIN_RUN: usize = if cfg!(feature = "part2") { 4 } else { 1 };
 const MAX_RUN: usize = if cfg!(feature = "part2") { 10 } else { 3 };
 
 fn main() {
+    cancel::start();
+    generate::run(141, generate);
+    validate::check(validate);
     println!("Day 17, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
 
     let map = load::numbers_map().unwrap();
@@ -16,10 +24,22 @@ fn main() {
     let lowest_unrestricted_costs = build_lowest_unrestricted_costs_map(&map, goal);
     let h = |(r, c): (usize, usize)| lowest_unrestricted_costs[r][c];
 
-    let cost = shortest_path(start, goal, h, &map);
+    debug!("Lowest unrestricted cost from start: {}", h(start));
+
+    let cost = shortest_path(start, goal, h, &map).unwrap_or_else(cancel::exit);
     println!("Shortest path: {}", cost);
 }
 
+// Returns the problems in the input, which is a grid of heat losses from 1 to 9
+fn validate(lines: &[String]) -> Vec<Problem> {
+    validate::grid(lines, "123456789")
+}
+
+// Returns a random map of heat losses. The size is the width and height.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    (0..size).map(|_| rng.string(size, "123456789")).collect()
+}
+
 // Builds a map of the shortest unrestricted distances from each cell to the goal using Dijkstra's algorithm
 fn build_lowest_unrestricted_costs_map(edge_costs: &Vec<Vec<i32>>, goal: (usize, usize)) -> Vec<Vec<i32>> {
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -100,7 +120,12 @@ impl PartialOrd for Node {
     }
 }
 
-fn shortest_path(start: (usize, usize), goal: (usize, usize), h: impl Fn((usize, usize)) -> i32, map: &Vec<Vec<i32>>) -> i32 {
+fn shortest_path(
+    start: (usize, usize),
+    goal: (usize, usize),
+    h: impl Fn((usize, usize)) -> i32,
+    map: &Vec<Vec<i32>>,
+) -> Result<i32, Cancelled> {
     let width = map[0].len();
     let height = map.len();
     let mut f = vec![vec![vec![std::i32::MAX; 2]; width]; height]; // f[r][c][d]
@@ -119,11 +144,13 @@ fn shortest_path(start: (usize, usize), goal: (usize, usize), h: impl Fn((usize,
     });
 
     while let Some(node) = open.pop() {
+        cancel::check()?;
         if node.position.0 == goal.0 && node.position.1 == goal.1 {
-            return node.g;
+            return Ok(node.g);
         }
         if node.f < f[node.position.0][node.position.1][node.position.2] {
             f[node.position.0][node.position.1][node.position.2] = node.f;
+            trace!("Expanding {:?} f={} g={}", node.position, node.f, node.g);
             let neighbors: Vec<Node> = get_neighbors(&node, &map);
             for mut n in neighbors {
                 let neighbor_location = (n.position.0, n.position.1);
@@ -132,7 +159,7 @@ fn shortest_path(start: (usize, usize), goal: (usize, usize), h: impl Fn((usize,
             }
         }
     }
-    std::i32::MAX
+    Ok(std::i32::MAX)
 }
 
 fn get_neighbors(node: &Node, map: &Vec<Vec<i32>>) -> Vec<Node> {
@@ -0,0 +1,42 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::Problem;
+use regex::Regex;
+
+#[derive(Debug)]
+pub struct Step {
+    pub direction: char,
+    pub distance: i32,
+    pub color: u32,
+}
+
+/// Parses the input into a vector of Steps
+/// The format of the input is:
+///     <dir> <length> '(#' <color> ')'
+///     where <dir> is one of U, D, L, R
+///     <length> is an integer
+///     <color> is a hex color code
+///
+/// Example input:
+/// U 2 (#ff0000)
+/// R 4 (#0000ff)
+/// D 3 (#00ff00)
+/// ...
+///
+pub fn parse_steps(lines: &[String]) -> Result<Vec<Step>, Problem> {
+    let steps_re = Regex::new(r"^([UDLR])\s+(\d+)\s+\(#([0-9a-fA-F]+)\)$").expect("Failed to compile regex");
+    let mut steps: Vec<Step> = Vec::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        let captures = steps_re.captures(line).ok_or_else(|| Problem::line(i, format!("Failed to parse: '{}'", line)))?;
+        let step = Step {
+            direction: captures[1].chars().next().unwrap(),
+            distance: captures[2].parse().map_err(|_| Problem::line(i, format!("Invalid distance: {}", &captures[2])))?,
+            color: u32::from_str_radix(&captures[3], 16)
+                .map_err(|_| Problem::line(i, format!("Invalid color: {}", &captures[3])))?,
+        };
+        steps.push(step);
+    }
+
+    Ok(steps)
+}
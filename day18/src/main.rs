@@ -1,21 +1,21 @@
-use common::load;
+#[cfg(not(feature = "part2"))]
+use common::render::{self, Color, Overlay};
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{
+    args, debug, load, polygon,
+    svg::{Style, Svg},
+};
+use day18::{parse_steps, Step};
 use regex::Regex;
 
-#[derive(Debug)]
-struct Step {
-    #[allow(dead_code)]
-    direction: char,
-    #[allow(dead_code)]
-    distance: i32,
-    #[allow(dead_code)]
-    color: u32,
-}
-
 fn main() {
+    generate::run(20, generate);
+    validate::check(validate);
     println!("Day 18, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
 
     let lines = load::lines().unwrap();
-    let steps: Vec<Step> = parse_steps(&lines);
+    let steps: Vec<Step> = validate::or_exit(parse_steps(&lines));
 
     #[cfg(not(feature = "part2"))]
     part1(&steps);
@@ -24,21 +24,73 @@ fn main() {
     part2(&steps);
 }
 
+// Returns the problems in the input. Each line is a step such as "R 6 (#70c710)".
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let step_regex = Regex::new(r"^[UDLR] \d+ \(#[0-9a-f]{5}[0-3]\)$").unwrap();
+    validate::each_line(lines, |line| match step_regex.is_match(line) {
+        true => Ok(()),
+        false => Err("expected \"<U|D|L|R> <distance> (#<color>)\"".to_string()),
+    })
+}
+
+// Returns a dig plan that follows a random loop. The loop is stretched by random gaps between its grid lines, small for
+// the directions and distances and large for the colors. The size is the width and height of the loop's grid.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let size = size.max(1);
+    let vertices = generate::random_loop(rng, size, size);
+    let corners: Vec<(i64, i64)> = (0..vertices.len())
+        .filter(|&i| {
+            let previous = vertices[(i + vertices.len() - 1) % vertices.len()];
+            let next = vertices[(i + 1) % vertices.len()];
+            previous.0 != next.0 && previous.1 != next.1
+        })
+        .map(|i| vertices[i])
+        .collect();
+
+    // Each grid line is moved to the sum of the gaps before it
+    let mut stretch = |max_gap: i64| {
+        let mut lines = vec![0];
+        for _ in 0..size {
+            lines.push(lines.last().unwrap() + rng.between(1, max_gap));
+        }
+        lines
+    };
+    let small = stretch(5);
+    let large = stretch(0xfffff / size as i64);
+
+    (0..corners.len())
+        .map(|i| {
+            let (x0, y0) = corners[i];
+            let (x1, y1) = corners[(i + 1) % corners.len()];
+            let (direction, code) = match (x1 - x0).signum() + 2 * (y1 - y0).signum() {
+                1 => ('R', 0),
+                2 => ('D', 1),
+                -1 => ('L', 2),
+                _ => ('U', 3),
+            };
+            let distance = |lines: &[i64]| {
+                (lines[x1 as usize] - lines[x0 as usize]).abs() + (lines[y1 as usize] - lines[y0 as usize]).abs()
+            };
+            format!("{} {} (#{:05x}{})", direction, distance(&small), distance(&large), code)
+        })
+        .collect()
+}
+
 #[cfg(not(feature = "part2"))]
 fn part1(steps: &[Step]) {
-    let extents = compute_extents(steps);
-//    println!("Extents: {:?}", extents);
-    let width = (extents.0).1 - (extents.0).0 + 1;
-    let height = (extents.1).1 - (extents.1).0 + 1;
-    let start: (usize, usize) = ((-(extents.0).0).try_into().unwrap(), (-(extents.1).0).try_into().unwrap());
-//    println!("Width: {}, Height: {}, Start: {:?}", width, height, start);
-
-    let mut map = create_map(width as usize, height as usize, start, steps);
+    let lagoon = dig_lagoon(steps);
+    if let Some(path) = args::value("--svg") {
+        write_svg(&lagoon.vertices, &path);
+    }
 
-    let interior_point = find_interior_point(&map);
-    flood_fill(&mut map, interior_point);
+    if render::enabled() {
+        let filled = Overlay::from_grid(&lagoon.filled, |&c| c == '#', Color::Blue).with_glyph('~');
+        let trench_overlay = Overlay::from_grid(&lagoon.trench, |&c| c == '#', Color::Yellow).with_glyph('#');
+        let start_overlay = Overlay::new(lagoon.fill_starts.iter().copied(), Color::Red).with_glyph('*');
+        render::print(&lagoon.trench, &[filled, trench_overlay, start_overlay]);
+    }
 
-    let volume = compute_volume(&map);
+    let volume = compute_volume(&lagoon.filled);
     println!("Volume: {}", volume);
 }
 
@@ -66,73 +118,70 @@ fn part2(steps: &[Step]) {
         })
         .collect::<Vec<(i64, i64)>>();
 
-    let inner_area = area(&vertices);
-    let perimeter_area = perimeter(&vertices) / 2;
-    let total_area = inner_area + perimeter_area + 1;
+    if let Some(path) = args::value("--svg") {
+        write_svg(&vertices, &path);
+    }
+
+    debug!("Vertices: {}", vertices.len());
+
+    let total_area = pick_volume(&vertices);
     println!("Area: {}", total_area);
 }
 
-#[cfg(feature = "part2")]
-fn area(vertices: &[(i64, i64)]) -> i64 {
-    let n = vertices.len();
-    let a: i64 = vertices.iter()
-        .zip(vertices.iter().cycle().skip(1))
-        .take(n) 
-        .map(|(v0, v1)| (v0.0 * v1.1) - (v1.0 * v0.1))
-        .sum();
-
-    a.abs() / 2
+// Returns the volume of the lagoon with the vertices of the dig path. The trench is one meter wide, so the lagoon is
+// every lattice point inside or on the dig path.
+#[cfg(any(test, feature = "part2"))]
+fn pick_volume(vertices: &[(i64, i64)]) -> i64 {
+    polygon::interior_points(vertices) + polygon::boundary_points(vertices)
 }
 
-#[cfg(feature = "part2")]
-fn perimeter(vertices: &[(i64, i64)]) -> i64 {
-    let n = vertices.len();
-    let p: i64 = vertices.iter()
-        .zip(vertices.iter().cycle().skip(1))
-        .take(n) 
-        .map(|(v0, v1)| (v1.0 - v0.0).abs() + (v1.1 - v0.1).abs())
-        .sum();
-
-    p
+// A map of the lagoon dug using the directions and distances of the steps
+#[cfg(any(test, not(feature = "part2")))]
+struct Lagoon {
+    filled: Vec<Vec<char>>,
+    #[cfg(not(feature = "part2"))]
+    trench: Vec<Vec<char>>,
+    #[cfg(not(feature = "part2"))]
+    vertices: Vec<(i64, i64)>,
+    #[cfg(not(feature = "part2"))]
+    fill_starts: Vec<(usize, usize)>,
 }
 
-/// Parses the input into a vector of Steps
-/// The format of the input is:
-///     <dir> <length> '(#' <color> ')'
-///     where <dir> is one of U, D, L, R
-///     <length> is an integer
-///     <color> is a hex color code
-///
-/// Example input:
-/// U 2 (#ff0000)
-/// R 4 (#0000ff)
-/// D 3 (#00ff00)
-/// ...
-///
-fn parse_steps(lines: &[String]) -> Vec<Step> {
-    let steps_re = Regex::new(r"^([UDLR])\s+(\d+)\s+\(#([0-9a-fA-F]+)\)$").expect("Failed to compile regex");
-    let mut steps: Vec<Step> = Vec::new();
-
-    for line in lines {
-        if let Some(captures) = steps_re.captures(line) {
-            let direction_m = captures.get(1).expect("Failed to parse direction");
-            let distance_m = captures.get(2).expect("Failed to parse distance");
-            let color_m = captures.get(3).expect("Failed to parse color");
-            let step = Step {
-                direction: direction_m.as_str().chars().next().expect("Missing direction"),
-                distance: distance_m.as_str().parse().expect("Invalid distance"),
-                color: u32::from_str_radix(&color_m.as_str(), 16).expect("Invalid color"),
-            };
-            steps.push(step);
-        } else {
-            panic!("Failed to parse: '{}'", line);
-        }
+// Digs the trench on a map and fills in its interior
+#[cfg(any(test, not(feature = "part2")))]
+fn dig_lagoon(steps: &[Step]) -> Lagoon {
+    let extents = compute_extents(steps);
+    debug!("Extents: {:?}", extents);
+    let width = (extents.0).1 - (extents.0).0 + 1;
+    let height = (extents.1).1 - (extents.1).0 + 1;
+    let start: (usize, usize) = ((-(extents.0).0).try_into().unwrap(), (-(extents.1).0).try_into().unwrap());
+    debug!("Width: {}, Height: {}, Start: {:?}", width, height, start);
+
+    let trench = create_map(width as usize, height as usize, start, steps);
+    let vertices = dig_path(start, steps);
+    let mut filled = trench.clone();
+    let fill_starts = fill_interior(&mut filled, &vertices);
+    debug!("Fill starts: {:?}", fill_starts);
+    Lagoon {
+        filled,
+        #[cfg(not(feature = "part2"))]
+        trench,
+        #[cfg(not(feature = "part2"))]
+        vertices,
+        #[cfg(not(feature = "part2"))]
+        fill_starts,
     }
+}
 
-    steps
+// Draws the dig path through the centers of the trench with the lagoon shaded
+fn write_svg(vertices: &[(i64, i64)], path: &str) {
+    let points: Vec<(f64, f64)> = vertices.iter().map(|&(x, y)| (x as f64, y as f64)).collect();
+    let mut svg = Svg::fitting(points.iter().copied(), 1.0);
+    svg.polygon(&points, &Style::new().fill_translucent("blue", 0.4).stroke("black", 1.0));
+    svg.save(path).unwrap_or_else(|e| panic!("Could not write \"{}\": {}", path, e));
 }
 
-#[cfg(not(feature = "part2"))]
+#[cfg(any(test, not(feature = "part2")))]
 fn compute_extents(steps: &[Step]) -> ((i32, i32), (i32, i32)) {
     let mut min_x = 0;
     let mut max_x = 0;
@@ -158,7 +207,7 @@ fn compute_extents(steps: &[Step]) -> ((i32, i32), (i32, i32)) {
     ((min_x, max_x), (min_y, max_y))
 }
 
-#[cfg(not(feature = "part2"))]
+#[cfg(any(test, not(feature = "part2")))]
 fn create_map(width: usize, height: usize, start: (usize, usize), steps: &[Step]) -> Vec<Vec<char>> {
     let mut map: Vec<Vec<char>> = vec![vec!['.'; width]; height];
 
@@ -181,50 +230,41 @@ fn create_map(width: usize, height: usize, start: (usize, usize), steps: &[Step]
     map
 }
 
-// fn print_map(map: &Vec<Vec<char>>) {
-//     for row in map {
-//         for cell in row {
-//             print!("{}", cell);
-//         }
-//         println!();
-//     }
-//     println!();
-// }
+#[cfg(any(test, not(feature = "part2")))]
+fn dig_path(start: (usize, usize), steps: &[Step]) -> Vec<(i64, i64)> {
+    let mut p = (start.0 as i64, start.1 as i64);
+    let mut vertices: Vec<(i64, i64)> = vec![p];
+    for step in steps {
+        let distance = step.distance as i64;
+        p = match step.direction {
+            'U' => (p.0, p.1 - distance),
+            'D' => (p.0, p.1 + distance),
+            'L' => (p.0 - distance, p.1),
+            'R' => (p.0 + distance, p.1),
+            _ => panic!("Unknown direction: {}", step.direction),
+        };
+        vertices.push(p);
+    }
+    vertices
+}
 
-#[cfg(not(feature = "part2"))]
-fn find_interior_point(map: &[Vec<char>]) -> (usize, usize) {
-    // We are guaranteed to find an interior point on the second row because a horizontal boundary must exist on the
-    // first row. We start at the left edge of the second row and move right until we find a wall. The next empty
-    // space is an interior point if the space up and left is a wall. Otherwise, we are still outside.
-    let y: usize = 1;
-    let mut x: usize = 0;
-
-    while x < map[y].len() {
-        // Find the next wall
-        while x < map[y].len() && map[y][x] != '#' {
-            x += 1;
-        }
-        if x == map[y].len() {
-            break;
-        }
-        // Find the next empty space
-        while x < map[y].len() && map[y][x] == '#' {
-            x += 1;
-        }
-        if x == map[y].len() {
-            break;
-        }
-        // Check if the space up and left is a wall
-        debug_assert!(x > 0);
-        debug_assert!(y > 0);
-        if map[y - 1][x - 1] == '#' {
-            return (x, y);
+// Flood fills each part of the interior of the trench, which may be split where the path runs alongside itself, and
+// returns the point each fill started from
+#[cfg(any(test, not(feature = "part2")))]
+fn fill_interior(map: &mut [Vec<char>], vertices: &[(i64, i64)]) -> Vec<(usize, usize)> {
+    let mut starts = Vec::new();
+    for y in 0..map.len() {
+        for x in 0..map[y].len() {
+            if map[y][x] != '#' && polygon::contains(vertices, (x as i64, y as i64)) {
+                flood_fill(map, (x, y));
+                starts.push((x, y));
+            }
         }
     }
-    panic!("No interior point found");
+    starts
 }
 
-#[cfg(not(feature = "part2"))]
+#[cfg(any(test, not(feature = "part2")))]
 fn flood_fill(map: &mut [Vec<char>], start: (usize, usize)) {
     let right_edge = map[0].len() - 1;
     let bottom_edge = map.len() - 1;
@@ -251,7 +291,31 @@ fn flood_fill(map: &mut [Vec<char>], start: (usize, usize)) {
     }
 }
 
-#[cfg(not(feature = "part2"))]
+#[cfg(any(test, not(feature = "part2")))]
 fn compute_volume(map: &[Vec<char>]) -> i32 {
     map.iter().flat_map(|row| row.iter()).filter(|&&cell| cell == '#').count() as i32
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::verify;
+
+    #[test]
+    fn volume() {
+        verify::property(100, 20, check_volume);
+    }
+
+    // Checks that flood filling a map of a random lagoon and counting lattice points with Pick's theorem agree
+    fn check_volume(rng: &mut Rng, size: usize) -> Result<(), String> {
+        let lines = generate(rng, size);
+        let steps = parse_steps(&lines).unwrap();
+        let expected = compute_volume(&dig_lagoon(&steps).filled) as i64;
+        let actual = pick_volume(&dig_path((0, 0), &steps));
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(format!("{}: volume {}, expected {}", lines.join(", "), actual, expected))
+        }
+    }
+}
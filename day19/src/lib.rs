@@ -0,0 +1,90 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::Problem;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+//a<2006:qkq,m>2090:A,rfg
+#[derive(Debug)]
+pub struct Rule {
+    pub attribute: Option<char>,
+    pub cmp: Option<char>,
+    pub threshold: Option<i32>,
+    pub workflow: String,
+}
+
+/// The rules of each workflow, by name
+pub type Workflows = HashMap<String, Vec<Rule>>;
+
+#[derive(Debug)]
+pub struct Part {
+    pub x: i32,
+    pub m: i32,
+    pub a: i32,
+    pub s: i32,
+}
+
+/// Parses the workflows, which end at the first empty line, and the parts after them
+pub fn parse_input(lines: &[String]) -> Result<(Workflows, Vec<Part>), Problem> {
+    let blank = lines.iter().position(|line| line.is_empty()).unwrap_or(lines.len());
+    let mut workflows = HashMap::new();
+    for (i, line) in lines[..blank].iter().enumerate() {
+        let (name, rules) = parse_workflow(line).map_err(|message| Problem::line(i, message))?;
+        workflows.insert(name, rules);
+    }
+    let mut parts = Vec::new();
+    for (i, line) in lines.iter().enumerate().skip(blank + 1) {
+        parts.push(parse_part(line).map_err(|message| Problem::line(i, message))?);
+    }
+    Ok((workflows, parts))
+}
+
+/// Parses a workflow such as "px{a<2006:qkq,m>2090:A,rfg}"
+pub fn parse_workflow(line: &str) -> Result<(String, Vec<Rule>), String> {
+    static WORKFLOW_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(\w+)\s*\{([^}]*)\}$").unwrap());
+    let captures = WORKFLOW_RE
+        .captures(line)
+        .ok_or_else(|| format!("Invalid workflow string: {}", line))?;
+    let name = captures[1].to_string();
+    let rules = captures[2].split(',').map(|s| parse_rule(s.trim())).collect::<Result<_, _>>()?;
+    Ok((name, rules))
+}
+
+/// Parses a rule such as "a<2006:qkq" or "rfg"
+pub fn parse_rule(s: &str) -> Result<Rule, String> {
+    static RULE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(?:([xmas])([<>])(\d+):(\w+)|(\w+))$").unwrap());
+    let captures = RULE_RE.captures(s).ok_or_else(|| format!("Invalid rule string: {}", s))?;
+    if let Some(match1) = captures.get(1) {
+        let attribute = match1.as_str().chars().next();
+        let cmp = captures[2].chars().next();
+        let threshold = Some(captures[3].parse().map_err(|_| format!("Invalid threshold: {}", &captures[3]))?);
+        let workflow = captures[4].to_string();
+        Ok(Rule {
+            attribute,
+            cmp,
+            threshold,
+            workflow,
+        })
+    } else {
+        Ok(Rule {
+            attribute: None,
+            cmp: None,
+            threshold: None,
+            workflow: captures[5].to_string(),
+        })
+    }
+}
+
+/// Parses a part such as "{x=787,m=2655,a=1222,s=2876}"
+pub fn parse_part(line: &str) -> Result<Part, String> {
+    static PART_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\{x=(\d+),m=(\d+),a=(\d+),s=(\d+)\}$").unwrap());
+    let captures = PART_RE.captures(line).ok_or_else(|| format!("Invalid part string: {}", line))?;
+    let rating = |i: usize| captures[i].parse().map_err(|_| format!("Invalid rating: {}", &captures[i]));
+    Ok(Part {
+        x: rating(1)?,
+        m: rating(2)?,
+        a: rating(3)?,
+        s: rating(4)?,
+    })
+}
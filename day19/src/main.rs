@@ -1,56 +1,30 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{debug, load, trace};
+use day19::{parse_input, Part, Workflows};
 use regex::Regex;
-use std::collections::HashMap;
-
-//a<2006:qkq,m>2090:A,rfg
-#[derive(Debug)]
-struct Rule {
-    attribute: Option<char>,
-    cmp: Option<char>,
-    threshold: Option<i32>,
-    workflow: String,
-}
-
-#[derive(Debug)]
-struct Part {
-    x: i32,
-    m: i32,
-    a: i32,
-    s: i32,
-}
 
 fn main() {
+    generate::run(500, generate);
+    validate::check(validate);
     println!("Day 19, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
 
     let lines = load::lines().unwrap();
 
-    // Parse each line as a workflow until an empty line is found.
-    let mut workflows = HashMap::new();
-    let mut i = 0;
-    while i < lines.len() {
-        if lines[i].is_empty() {
-            break;
-        }
-        let (name, rules) = parse_workflow(&lines[i]);
-        workflows.insert(name, rules);
-        i += 1;
-    }
+    let (workflows, parts) = validate::or_exit(parse_input(&lines));
 
-    // Parse the remaining lines as part descriptions
-    let mut parts = Vec::new();
-    i += 1;
-    while i < lines.len() {
-        parts.push(parse_part(&lines[i]));
-        i += 1;
-    }
+    debug!("Workflows: {}, parts: {}", workflows.len(), parts.len());
 
     let mut sum: i64 = 0;
 
     // Run each part through the workflow
     for part in parts {
         let mut workflow = process_workflow(&workflows, "in", &part);
+        trace!("{:?}: in -> {}", part, workflow);
         while workflow != "A" && workflow != "R" {
-            workflow = process_workflow(&workflows, workflow.as_str(), &part);
+            let next = process_workflow(&workflows, workflow.as_str(), &part);
+            trace!("{:?}: {} -> {}", part, workflow, next);
+            workflow = next;
         }
 
         if workflow == "A" {
@@ -63,7 +37,77 @@ fn main() {
     println!("Sum: {}", sum);
 }
 
-fn process_workflow(workflows: &HashMap<String, Vec<Rule>>, workflow: &str, part: &Part) -> String {
+// Returns the problems in the input: workflows such as "px{a<2006:qkq,m>2090:A,rfg}", a blank line, then parts such as
+// "{x=787,m=2655,a=1222,s=2876}". The "in" workflow and every workflow a rule sends a part to must be defined.
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let workflow_regex = Regex::new(r"^(\w+)\{((?:[xmas][<>]\d+:\w+,)*\w+)\}$").unwrap();
+    let part_regex = Regex::new(r"^\{x=\d+,m=\d+,a=\d+,s=\d+\}$").unwrap();
+    let mut problems = Vec::new();
+    let blank = lines.iter().position(|line| line.is_empty()).unwrap_or(lines.len());
+    let mut names = vec!["A", "R"];
+    let mut targets = Vec::new();
+    for (i, line) in lines[..blank].iter().enumerate() {
+        match workflow_regex.captures(line) {
+            Some(captures) => {
+                names.push(captures.get(1).unwrap().as_str());
+                for rule in captures.get(2).unwrap().as_str().split(',') {
+                    targets.push((i, rule.rsplit(':').next().unwrap()));
+                }
+            }
+            None => problems.push(Problem::line(i, "expected \"<name>{<rules>}\"")),
+        }
+    }
+    if !names.contains(&"in") {
+        problems.push(Problem::line(0, "missing the \"in\" workflow"));
+    }
+    for (i, target) in targets {
+        if !names.contains(&target) {
+            problems.push(Problem::line(i, format!("workflow {:?} is not defined", target)));
+        }
+    }
+    for (i, line) in lines.iter().enumerate().skip(blank + 1) {
+        if !part_regex.is_match(line) {
+            problems.push(Problem::line(i, "expected \"{x=<n>,m=<n>,a=<n>,s=<n>}\""));
+        }
+    }
+    problems
+}
+
+// Returns random workflows and parts. Rules only send parts to later workflows, so no part loops forever. The size is
+// the number of workflows and of parts.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let size = size.max(1);
+    let mut names = rng.names(size - 1, 3, "abcdefghijklmnopqrstuvwxyz", &["in"]);
+    names.insert(0, "in".to_string());
+    let mut lines: Vec<String> = (0..size)
+        .map(|i| {
+            let target = |rng: &mut Rng| match rng.below(size - i + 1) {
+                0 => "A".to_string(),
+                1 => "R".to_string(),
+                n => names[i + n - 1].clone(),
+            };
+            let mut rules: Vec<String> = (0..rng.between(1, 3))
+                .map(|_| {
+                    let attribute = *rng.choose(&['x', 'm', 'a', 's']);
+                    let comparison = *rng.choose(&['<', '>']);
+                    let threshold = rng.between(1, 4000);
+                    format!("{}{}{}:{}", attribute, comparison, threshold, target(rng))
+                })
+                .collect();
+            rules.push(target(rng));
+            format!("{}{{{}}}", names[i], rules.join(","))
+        })
+        .collect();
+    rng.shuffle(&mut lines);
+    lines.push(String::new());
+    for _ in 0..size {
+        let ratings: Vec<i64> = (0..4).map(|_| rng.between(1, 4000)).collect();
+        lines.push(format!("{{x={},m={},a={},s={}}}", ratings[0], ratings[1], ratings[2], ratings[3]));
+    }
+    lines
+}
+
+fn process_workflow(workflows: &Workflows, workflow: &str, part: &Part) -> String {
     if let Some(rules) = workflows.get(workflow) {
         for rule in rules {
             if let Some(attribute) = rule.attribute {
@@ -98,55 +142,3 @@ fn process_workflow(workflows: &HashMap<String, Vec<Rule>>, workflow: &str, part
     }
     panic!("No rule matched for workflow {:?}", workflow);
 }
-
-fn parse_workflow(line: &str) -> (String, Vec<Rule>) {
-    let workflow_re = Regex::new(r"^(\w+)\s*\{([^}]*)\}$").unwrap();
-    if let Some(captures) = workflow_re.captures(line) {
-        let name = captures.get(1).unwrap().as_str().to_string();
-        let rule_strings: Vec<&str> = captures.get(2).unwrap().as_str().split(',').map(|s| s.trim()).collect();
-        let rules = rule_strings.into_iter().map(parse_rule).collect();
-        (name, rules)
-    } else {
-        panic!("Invalid workflow string: {}", line);
-    }
-}
-
-fn parse_rule(s: &str) -> Rule {
-    let rule_re = Regex::new(r"^([xmas])([<>])(\d+):(\w+)|(\w+)$").unwrap();
-    if let Some(captures) = rule_re.captures(s) {
-        if let Some(match1) = captures.get(1) {
-            let attribute = match1.as_str().chars().next();
-            let cmp = captures.get(2).unwrap().as_str().chars().next();
-            let threshold = captures.get(3).map(|m| m.as_str().parse().unwrap());
-            let workflow = captures.get(4).unwrap().as_str().to_string();
-            return Rule {
-                attribute,
-                cmp,
-                threshold,
-                workflow,
-            };
-        } else {
-            let workflow = captures.get(5).unwrap().as_str().to_string();
-            return Rule {
-                attribute: None,
-                cmp: None,
-                threshold: None,
-                workflow,
-            };
-        }
-    }
-    panic!("Invalid rule string: {}", s);
-}
-
-fn parse_part(line: &str) -> Part {
-    let re = Regex::new(r"\{x=(\d+),m=(\d+),a=(\d+),s=(\d+)\}").unwrap();
-    if let Some(captures) = re.captures(line) {
-        let x = captures.get(1).unwrap().as_str().parse().unwrap();
-        let m = captures.get(2).unwrap().as_str().parse().unwrap();
-        let a = captures.get(3).unwrap().as_str().parse().unwrap();
-        let s = captures.get(4).unwrap().as_str().parse().unwrap();
-        Part { x, m, a, s }
-    } else {
-        panic!("Invalid part string: {}", line);
-    }
-}
@@ -0,0 +1,72 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::Problem;
+use regex::Regex;
+use std::collections::HashMap;
+
+#[derive(Debug)]
+pub struct Module {
+    pub module_type: String,
+    pub sources: HashMap<String, bool>,
+    pub destinations: Vec<String>,
+    pub state: bool,
+}
+
+impl Module {
+    /// Creates a module in its initial state with no sources
+    pub fn new(module_type: &str, destinations: &[String]) -> Self {
+        Self {
+            module_type: module_type.to_string(),
+            sources: HashMap::new(),
+            destinations: destinations.to_vec(),
+            state: false,
+        }
+    }
+}
+
+/// Parses the modules, one per line, and connects each module to its sources
+pub fn load_modules(lines: &[String]) -> Result<HashMap<String, Module>, Problem> {
+    let mut modules: HashMap<String, Module> = HashMap::new();
+
+    // First add the output and rx modules They are not defined in the input but modules output to them
+    modules.insert("output".to_string(), Module::new("*", &[]));
+    modules.insert("rx".to_string(), Module::new("*", &[]));
+
+    // Create the modules from the input
+    let re = Regex::new(r"^([%&]?)(\w+)\s*->\s*([,\w\s]+)$").expect("Invalid regex");
+    let mut lines_by_name = HashMap::new();
+    for (i, line) in lines.iter().enumerate() {
+        let captures = re.captures(line).ok_or_else(|| Problem::line(i, format!("Failed to parse line: {}", line)))?;
+        let module_type = captures.get(1).map_or("X", |m| m.as_str());
+        let name = captures[2].to_string();
+        let destinations = captures[3].split(",").map(|s| s.trim().to_string()).collect::<Vec<String>>();
+        lines_by_name.insert(name.clone(), i);
+        if name == "broadcaster" {
+            modules.insert(name, Module::new("<", &destinations));
+        } else {
+            modules.insert(name, Module::new(module_type, &destinations));
+        }
+    }
+
+    // Get the sources for each module
+    let mut sources_by_destination: HashMap<String, HashMap<String, bool>> = HashMap::new();
+    for (name, module) in &modules {
+        for destination in &module.destinations {
+            if !modules.contains_key(destination) {
+                let message = format!("Module {} has an unknown destination: {}", name, destination);
+                return Err(Problem::line(lines_by_name[name], message));
+            }
+            sources_by_destination
+                .entry(destination.clone())
+                .or_default()
+                .insert(name.clone(), false);
+        }
+    }
+
+    // Save each module's sources
+    for (name, module) in &mut modules {
+        module.sources = sources_by_destination.remove(name).unwrap_or_default();
+    }
+
+    Ok(modules)
+}
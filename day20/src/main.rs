@@ -1,90 +1,119 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{args, cancel, debug, dot, load};
+#[cfg(feature = "part2")]
+use common::{cancel::Cancelled, info, progress::Progress};
+#[cfg(feature = "part2")]
+use common::math;
+use day20::{load_modules, Module};
 use regex::Regex;
 use std::collections::{HashMap, VecDeque};
 
-#[derive(Debug)]
-struct Module {
-    module_type: String,
-    sources: HashMap<String, bool>,
-    destinations: Vec<String>,
-    state: bool,
-}
-
-impl Module {
-    fn new(module_type: &str, destinations: &[String]) -> Self {
-        Self {
-            module_type: module_type.to_string(),
-            sources: HashMap::new(),
-            destinations: destinations.to_vec(),
-            state: false,
-        }
-    }
-}
-
 fn main() {
+    cancel::start();
+    generate::run(12, generate);
+    validate::check(validate);
     println!("Day 20, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
 
     let lines = load::lines().unwrap();
-    let mut modules = load_modules(&lines);
+    let mut modules = validate::or_exit(load_modules(&lines));
+
+    debug!("Modules: {}", modules.len());
+
+    if let Some(path) = args::value("--dot") {
+        write_dot(&modules, &path);
+    }
 
     #[cfg(not(feature = "part2"))]
     part1(&mut modules);
 
     #[cfg(feature = "part2")]
-    part2(&mut modules);
+    part2(&mut modules).unwrap_or_else(cancel::exit);
 }
 
-fn load_modules(lines: &[String]) -> HashMap<String, Module> {
-    let mut modules: HashMap<String, Module> = HashMap::new();
-
-    // First add the output and rx modules They are not defined in the input but modules output to them
-    modules.insert("output".to_string(), Module::new("*", &[]));
-    modules.insert("rx".to_string(), Module::new("*", &[]));
-
-    // Create the modules from the input
-    let re = Regex::new(r"^([%&]?)(\w+)\s*->\s*([,\w\s]+)$").expect("Invalid regex");
-    for line in lines {
-        let captures = re.captures(line).unwrap_or_else(|| panic!("Failed to parse line: {}", line));
-        let module_type = captures.get(1).map_or("X", |m| m.as_str());
-        let name = captures.get(2).expect("Failed to get module name").as_str().to_string();
-        let destinations = captures
-            .get(3)
-            .expect("Failed to get destinations")
-            .as_str()
-            .split(",")
-            .map(|s| s.trim().to_string())
-            .collect::<Vec<String>>();
-        if name == "broadcaster" {
-            modules.insert(name, Module::new("<", &destinations));
-        } else {
-            modules.insert(name, Module::new(module_type, &destinations));
+// Returns the problems in the input. Each line is a module such as "%a -> b, c", and every destination must be defined
+// or be "output" or "rx".
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let module_regex = Regex::new(r"^([%&]?)(\w+) -> (\w+(?:, \w+)*)$").unwrap();
+    let mut problems = Vec::new();
+    let mut names = vec!["output", "rx"];
+    let mut destinations = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        match module_regex.captures(line) {
+            Some(captures) => {
+                names.push(captures.get(2).unwrap().as_str());
+                destinations.extend(captures.get(3).unwrap().as_str().split(", ").map(|d| (i, d)));
+            }
+            None => problems.push(Problem::line(i, "expected \"[%|&]<name> -> <destinations>\"")),
         }
     }
+    if !names.contains(&"broadcaster") {
+        problems.push(Problem::line(0, "missing the broadcaster"));
+    }
+    for (i, destination) in destinations {
+        if !names.contains(&destination) {
+            problems.push(Problem::line(i, format!("module {:?} is not defined", destination)));
+        }
+    }
+    problems
+}
 
-    // Get the sources for each module
-    let mut sources_by_destination: HashMap<String, HashMap<String, bool>> = HashMap::new();
-    for (name, module) in &modules {
-        for destination in &module.destinations {
-            if modules.get(destination).is_none() {
-                panic!("Module {} has an unknown destination: {}", name, destination);
+// Returns a network like the puzzle's: the broadcaster feeds four counters of flip-flops, each with a conjunction that
+// resets it after a random number of presses. The conjunctions vd, ns, bh, and dl invert their outputs into zh, which
+// sends to rx. The size is the number of flip-flops in each counter.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let bits = size.clamp(2, 20);
+    let mut names = rng.names(4 * (bits + 1), 2, "abcdefghijklmnopqrstuvwxyz", &["vd", "ns", "bh", "dl", "zh", "rx"]);
+    let mut lines = Vec::new();
+    let mut starts = Vec::new();
+    for inverter in ["vd", "ns", "bh", "dl"] {
+        // The counter is reset when it reaches the period, whose first and last bits are set
+        let period = (1 << (bits - 1)) | rng.below(1 << (bits - 1)) | 1;
+        debug!("{} is triggered every {} presses", inverter, period);
+        let flip_flops = names.split_off(names.len() - bits);
+        let hub = names.pop().unwrap();
+        let mut hub_destinations = vec![inverter.to_string(), flip_flops[0].clone()];
+        for (bit, flip_flop) in flip_flops.iter().enumerate() {
+            let mut destinations = Vec::new();
+            if bit + 1 < bits {
+                destinations.push(flip_flops[bit + 1].clone());
+            }
+            if period & (1 << bit) != 0 {
+                destinations.push(hub.clone());
+            } else {
+                hub_destinations.push(flip_flop.clone());
             }
-            sources_by_destination
-                .entry(destination.clone())
-                .or_insert_with(HashMap::new)
-                .insert(name.clone(), false);
+            lines.push(format!("%{} -> {}", flip_flop, destinations.join(", ")));
         }
+        lines.push(format!("&{} -> {}", hub, hub_destinations.join(", ")));
+        lines.push(format!("&{} -> zh", inverter));
+        starts.push(flip_flops[0].clone());
     }
+    lines.push("&zh -> rx".to_string());
+    lines.push(format!("broadcaster -> {}", starts.join(", ")));
+    rng.shuffle(&mut lines);
+    lines
+}
 
-    // Save each module's sources
-    for (name, module) in &mut modules {
-        module.sources = if let Some(sources) = sources_by_destination.get(name) {
-            sources.clone()
-        } else {
-            HashMap::new()
+// Writes the module network as a graph with a node shape for each module type
+fn write_dot(modules: &HashMap<String, Module>, path: &str) {
+    let mut graph = dot::Graph::directed();
+    let mut names: Vec<&String> = modules.keys().collect();
+    names.sort();
+    for name in names {
+        let module = &modules[name];
+        let (shape, label) = match module.module_type.as_str() {
+            "%" => ("box", format!("%{}", name)),
+            "&" => ("invhouse", format!("&{}", name)),
+            "<" => ("doublecircle", name.clone()),
+            _ => ("doubleoctagon", name.clone()),
         };
+        graph.node(name, &[("shape", shape), ("label", &label)]);
+        for destination in &module.destinations {
+            graph.edge(name, destination, &[]);
+        }
     }
-
-    modules
+    graph.save(path).unwrap_or_else(|e| panic!("Could not write \"{}\": {}", path, e));
 }
 
 #[cfg(not(feature = "part2"))]
@@ -105,38 +134,46 @@ fn part1(modules: &mut HashMap<String, Module>) {
 }
 
 #[cfg(feature = "part2")]
-fn part2(modules: &mut HashMap<String, Module>) {
+fn part2(modules: &mut HashMap<String, Module>) -> Result<(), Cancelled> {
     let mut count: i64 = 0;
     let mut vd_triggered: Option<i64> = None;
     let mut ns_triggered: Option<i64> = None;
     let mut bh_triggered: Option<i64> = None;
     let mut dl_triggered: Option<i64> = None;
+    let mut progress = Progress::new("Button presses", None);
     loop {
+        cancel::check()?;
         run(modules);
         count += 1;
+        progress.tick();
         if vd_triggered.is_none() && modules.get("vd").unwrap().state {
             modules.get_mut("vd").unwrap().state = false; // reset vd
             vd_triggered = Some(count);
+            info!("vd triggered after {} presses", count);
         }
         if ns_triggered.is_none() && modules.get("ns").unwrap().state {
             modules.get_mut("ns").unwrap().state = false; // reset ns
             ns_triggered = Some(count);
+            info!("ns triggered after {} presses", count);
         }
         if bh_triggered.is_none() && modules.get("bh").unwrap().state {
             modules.get_mut("bh").unwrap().state = false; // reset bh
             bh_triggered = Some(count);
+            info!("bh triggered after {} presses", count);
         }
         if dl_triggered.is_none() && modules.get("dl").unwrap().state {
             modules.get_mut("dl").unwrap().state = false; // reset dl
             dl_triggered = Some(count);
+            info!("dl triggered after {} presses", count);
         }
-        // Get the product of all triggered counts or None if any didn't trigger
-        let product = vd_triggered.and_then(|vd| {
-            ns_triggered.and_then(|ns| bh_triggered.and_then(|bh| dl_triggered.and_then(|dl| Some(vd * ns * bh * dl))))
-        });
-        if let Some(p) = product {
-            println!("Product of triggered counts: {}", p);
-            break;
+        // rx goes low when all four trigger on the same press, which is the least common multiple of their counts
+        if let (Some(vd), Some(ns), Some(bh), Some(dl)) = (vd_triggered, ns_triggered, bh_triggered, dl_triggered) {
+            progress.finish();
+            match math::lcm_of([vd, ns, bh, dl]) {
+                Some(presses) => println!("Presses: {}", presses),
+                None => println!("Presses: more than {}", i64::MAX),
+            }
+            return Ok(());
         }
     }
 }
@@ -1,14 +1,20 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{
+    debug, load,
+    render::{self, Color, Overlay},
+};
 use std::collections::HashSet;
 
 const NUMBER_OF_STEPS: i64 = 64;
 
 fn main() {
+    generate::run(65, generate);
+    validate::check(validate);
     println!("Day 21, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
 
     // Load the map
     let map = load::map().unwrap();
-    //    print_map(&map);
     let width = map[0].len();
     let height = map.len();
 
@@ -29,6 +35,8 @@ fn main() {
         let mut next = Vec::new();
         (next, neighbors) = (neighbors, next);
 
+        debug!("Step {}: {} cells to expand, {} terminals", step, next.len(), terminals.len());
+
         // Check each cell and add the neighbors
         for (x, y) in next.iter() {
             if step % 2 != 0 {
@@ -65,13 +73,48 @@ fn main() {
                 }
             }
         }
-        //        print_map_with_terminals(&map, &terminals);
+    }
+
+    if render::enabled() {
+        let reached = Overlay::new(terminals.iter().copied(), Color::Green).with_glyph('O');
+        let start_overlay = Overlay::new([start], Color::Red).with_glyph('S');
+        render::print(&map, &[reached, start_overlay]);
     }
 
     // Print the result
     println!("Number of terminals: {}", terminals.len());
 }
 
+// Returns the problems in the input, which is a grid of '.' and '#' with one 'S'
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let mut problems = validate::grid(lines, ".#S");
+    problems.extend(validate::exactly_one(lines, 'S'));
+    problems
+}
+
+// Returns a random garden with the start in the middle and clear paths along the middle row and column and around the
+// edges, like the puzzle's. The size is the distance from the middle to the edges.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let width = 2 * size + 1;
+    (0..width)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    if x == size && y == size {
+                        'S'
+                    } else if x == size || y == size || x == 0 || y == 0 || x == width - 1 || y == width - 1 {
+                        '.'
+                    } else if rng.chance(0.1) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
 fn find_start(map: &Vec<Vec<char>>) -> Option<(usize, usize)> {
     map.iter().enumerate().find_map(|(y, row)| {
         row.iter()
@@ -0,0 +1,32 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::Problem;
+
+/// A brick's two corners
+pub type Brick = ((i32, i32, i32), (i32, i32, i32));
+
+/// Parses the bricks, one per line, such as "1,0,1~1,2,1"
+pub fn parse_bricks(lines: &[String]) -> Result<Vec<Brick>, Problem> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| parse_brick(line).map_err(|message| Problem::line(i, message)))
+        .collect()
+}
+
+/// Parses a brick's two corners
+pub fn parse_brick(line: &str) -> Result<Brick, String> {
+    let (c0, c1) = line.split_once('~').ok_or("missing '~'")?;
+    Ok((parse_corner(c0)?, parse_corner(c1)?))
+}
+
+fn parse_corner(s: &str) -> Result<(i32, i32, i32), String> {
+    let coordinates = s
+        .split(',')
+        .map(|token| token.parse().map_err(|_| format!("{:?} is not a number", token)))
+        .collect::<Result<Vec<i32>, String>>()?;
+    match coordinates[..] {
+        [x, y, z] => Ok((x, y, z)),
+        _ => Err(format!("expected 3 coordinates, found {}", coordinates.len())),
+    }
+}
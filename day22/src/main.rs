@@ -1,18 +1,31 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{
+    debug,
+    frames::Recorder,
+    load,
+    render::{Color, Overlay},
+    trace,
+};
+use day22::parse_bricks;
+use std::collections::HashSet;
 
 fn main() {
+    generate::run(1200, generate);
+    validate::check(validate);
     println!("Day 22, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
 
     // Load the map
     let lines = load::lines().unwrap();
-    let mut bricks = parse_bricks(&lines);
+    let mut bricks = validate::or_exit(parse_bricks(&lines));
 
     // Sort the bricks by z
     bricks.sort_by(|a, b| a.0 .2.cmp(&b.0 .2));
     let extents = find_extents(&bricks);
+    debug!("Bricks: {}, extents: {:?}", bricks.len(), extents);
 
     // Let the bricks fall and sort again afterwards
-    drop(&mut bricks, extents);
+    drop(&mut bricks, extents, Recorder::from_args().as_mut());
     bricks.sort_by(|a, b| a.0 .2.cmp(&b.0 .2));
 
     // For each brick, find which bricks it supports
@@ -26,15 +39,43 @@ fn main() {
     println!("Number of disintegratable bricks: {}", disintegratable.len());
 }
 
-fn parse_bricks(lines: &Vec<String>) -> Vec<((i32, i32, i32), (i32, i32, i32))> {
-    let mut bricks: Vec<((i32, i32, i32), (i32, i32, i32))> = Vec::new();
-    for line in lines {
-        let corners: Vec<&str> = line.split("~").collect();
-        let c0: Vec<i32> = corners[0].split(",").map(|s| s.parse().unwrap()).collect();
-        let c1: Vec<i32> = corners[1].split(",").map(|s| s.parse().unwrap()).collect();
-        bricks.push(((c0[0], c0[1], c0[2]), (c1[0], c1[1], c1[2])));
+// Returns the problems in the input. Each line is a brick such as "1,0,1~1,2,1".
+fn validate(lines: &[String]) -> Vec<Problem> {
+    validate::each_line(lines, |line| {
+        let (c0, c1) = line.split_once('~').ok_or("missing '~'")?;
+        for corner in [c0, c1] {
+            let coordinates = validate::numbers(corner, ',')?;
+            if coordinates.len() != 3 {
+                return Err(format!("expected 3 coordinates, found {}", coordinates.len()));
+            }
+        }
+        Ok(())
+    })
+}
+
+// Returns a random snapshot of bricks up to 4 cubes long in a 10 by 10 column, with no bricks overlapping. The size is
+// the number of bricks.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let height = size as i64 / 4 + 10;
+    let mut occupied: HashSet<(i64, i64, i64)> = HashSet::new();
+    let mut lines = Vec::new();
+    while lines.len() < size {
+        let start = (rng.between(0, 9), rng.between(0, 9), rng.between(1, height));
+        let length = rng.between(0, 3);
+        let end = match rng.below(3) {
+            0 => ((start.0 + length).min(9), start.1, start.2),
+            1 => (start.0, (start.1 + length).min(9), start.2),
+            _ => (start.0, start.1, start.2 + length),
+        };
+        let cubes: Vec<(i64, i64, i64)> = (start.0..=end.0)
+            .flat_map(|x| (start.1..=end.1).flat_map(move |y| (start.2..=end.2).map(move |z| (x, y, z))))
+            .collect();
+        if cubes.iter().all(|cube| !occupied.contains(cube)) {
+            occupied.extend(cubes);
+            lines.push(format!("{},{},{}~{},{},{}", start.0, start.1, start.2, end.0, end.1, end.2));
+        }
     }
-    bricks
+    lines
 }
 
 fn find_extents(bricks: &Vec<((i32, i32, i32), (i32, i32, i32))>) -> ((i32, i32, i32), (i32, i32, i32)) {
@@ -49,16 +90,52 @@ fn find_extents(bricks: &Vec<((i32, i32, i32), (i32, i32, i32))>) -> ((i32, i32,
     extents
 }
 
-fn drop(bricks: &mut Vec<((i32, i32, i32), (i32, i32, i32))>, extents: ((i32, i32, i32), (i32, i32, i32))) {
+fn drop(
+    bricks: &mut Vec<((i32, i32, i32), (i32, i32, i32))>,
+    extents: ((i32, i32, i32), (i32, i32, i32)),
+    mut recorder: Option<&mut Recorder>,
+) {
     let mut heights: Vec<Vec<i32>> = vec![vec![1; extents.1 .0 as usize + 1]; extents.1 .1 as usize + 1];
-    for brick in bricks {
-        let distance = brick.0 .2 - highest_z_under(&brick, &heights);
+    for i in 0..bricks.len() {
+        let brick = &mut bricks[i];
+        let distance = brick.0 .2 - highest_z_under(brick, &heights);
+        trace!("Brick {} {:?} falls {}", i, brick, distance.max(0));
         if distance > 0 {
             brick.0 .2 -= distance;
             brick.1 .2 -= distance;
         }
-        pile(&brick, &mut heights);
+        pile(brick, &mut heights);
+
+        if let Some(recorder) = recorder.as_deref_mut() {
+            let (view, cells) = side_view(bricks, extents, i);
+            recorder.record(&view, &[Overlay::new(cells, Color::Red)]).expect("Failed to write frame");
+        }
+    }
+}
+
+// Returns a view of the bricks looking along the y axis with the ground at the bottom, and the cells covered by the
+// brick at index `highlight`
+fn side_view(
+    bricks: &[((i32, i32, i32), (i32, i32, i32))],
+    extents: ((i32, i32, i32), (i32, i32, i32)),
+    highlight: usize,
+) -> (Vec<Vec<char>>, Vec<(usize, usize)>) {
+    let top = extents.1 .2 as usize;
+    let mut view: Vec<Vec<char>> = vec![vec!['.'; extents.1 .0 as usize + 1]; top + 1];
+    view[top] = vec!['='; extents.1 .0 as usize + 1];
+    let mut cells: Vec<(usize, usize)> = Vec::new();
+    for (i, brick) in bricks.iter().enumerate() {
+        for x in brick.0 .0..=brick.1 .0 {
+            for z in brick.0 .2..=brick.1 .2 {
+                let cell = (x as usize, top - z as usize);
+                view[cell.1][cell.0] = '#';
+                if i == highlight {
+                    cells.push(cell);
+                }
+            }
+        }
     }
+    (view, cells)
 }
 
 fn pile(brick: &((i32, i32, i32), (i32, i32, i32)), heights: &mut Vec<Vec<i32>>) {
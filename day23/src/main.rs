@@ -1,4 +1,11 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{
+    cancel::{self, Cancelled},
+    debug, info, load,
+    progress::Progress,
+    trace,
+};
 use std::collections::HashMap;
 
 #[derive(Debug)]
@@ -31,6 +38,9 @@ struct Path {
 }
 
 fn main() {
+    cancel::start();
+    generate::run(5, generate);
+    validate::check(validate);
     println!("Day 23, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
 
     // Load the map
@@ -46,12 +56,69 @@ fn main() {
 
     follow_path(&map, 0, (0, 1), false, &mut nodes);
 
+    debug!("Graph: {} nodes, {} edges", nodes.len(), nodes.iter().map(|n| n.edges.len()).sum::<usize>());
+
     // Now we have a graph, let's find all of the paths
-    let paths = enumerate_paths(&nodes, 0, 1);
+    let paths = enumerate_paths(&nodes, 0, 1).unwrap_or_else(cancel::exit);
+    info!("Paths: {}", paths.len());
     let max_cost = paths.iter().map(|p| p.cost).max().unwrap();
     println!("Max cost: {}", max_cost);
 }
 
+// Returns the problems in the input, which is a grid of paths, forest, and slopes with openings in the top and bottom
+// rows next to the corners
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let mut problems = validate::grid(lines, "#.^>v<");
+    if problems.is_empty() {
+        let width = lines[0].len();
+        if width < 3 || lines.len() < 3 {
+            problems.push(Problem::line(0, "the grid is too small"));
+        } else {
+            if lines[0].as_bytes()[1] != b'.' {
+                problems.push(Problem::at(0, 1, "expected the start '.'"));
+            }
+            if lines[lines.len() - 1].as_bytes()[width - 2] != b'.' {
+                problems.push(Problem::at(lines.len() - 1, width - 2, "expected the goal '.'"));
+            }
+        }
+    }
+    problems
+}
+
+// Returns a maze like the puzzle's: a square lattice of junctions joined by straight paths of random lengths, with
+// slopes leading right and down at both ends of each path. The start is above the top-left junction and the goal is
+// below the bottom-right junction. The size is the number of junctions along each side.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let size = size.max(2);
+    let mut junctions = vec![1];
+    for _ in 1..size {
+        junctions.push(junctions.last().unwrap() + rng.between(3, 12) as usize);
+    }
+    let width = junctions.last().unwrap() + 2;
+    let mut map = vec![vec!['#'; width]; width];
+    map[0][1] = '.';
+    map[width - 1][width - 2] = '.';
+    for &y in &junctions {
+        for &x in &junctions {
+            map[y][x] = '.';
+        }
+    }
+    for i in 0..size {
+        for j in 0..size - 1 {
+            // The path right from junction (j, i) and the path down from junction (i, j)
+            let (a, b) = (junctions[j], junctions[j + 1]);
+            let path = |k: usize, slope: char| if k == a + 1 || k == b - 1 { slope } else { '.' };
+            for (k, cell) in map[junctions[i]].iter_mut().enumerate().take(b).skip(a + 1) {
+                *cell = path(k, '>');
+            }
+            for (k, row) in map.iter_mut().enumerate().take(b).skip(a + 1) {
+                row[junctions[i]] = path(k, 'v');
+            }
+        }
+    }
+    map.iter().map(|row| row.iter().collect()).collect()
+}
+
 fn follow_path(map: &Vec<Vec<char>>, from: usize, mut dir: (i32, i32), mut directed: bool, nodes: &mut Vec<Node>) {
     let from_node = &nodes[from];
     let mut pos = ((from_node.x as i32 + dir.0) as usize, (from_node.y as i32 + dir.1) as usize);
@@ -288,28 +355,42 @@ fn is_node(map: &Vec<Vec<char>>, pos: (usize, usize)) -> bool {
     path_count > 2
 }
 
-fn enumerate_paths(nodes: &Vec<Node>, from: usize, goal: usize) -> Vec<Path> {
+fn enumerate_paths(nodes: &Vec<Node>, from: usize, goal: usize) -> Result<Vec<Path>, Cancelled> {
     let mut paths = Vec::new();
     let mut path = Vec::new();
     path.push(from);
-    enumerate_paths_rec(nodes, from, goal, path.clone(), 0, &mut paths);
-    paths
+    let mut progress = Progress::new("Paths found", None);
+    enumerate_paths_rec(nodes, from, goal, path.clone(), 0, &mut paths, &mut progress)?;
+    progress.finish();
+    Ok(paths)
 }
 
-fn enumerate_paths_rec(nodes: &Vec<Node>, from: usize, goal: usize, mut path: Vec<usize>, total_cost: i32, paths: &mut Vec<Path>) {
+fn enumerate_paths_rec(
+    nodes: &Vec<Node>,
+    from: usize,
+    goal: usize,
+    mut path: Vec<usize>,
+    total_cost: i32,
+    paths: &mut Vec<Path>,
+    progress: &mut Progress,
+) -> Result<(), Cancelled> {
+    cancel::check()?;
     let from_node = &nodes[from];
     for (next, cost) in &from_node.edges {
         if *next == goal {
+            trace!("Path to goal with cost {}", total_cost + cost);
             path.push(goal);
             paths.push(Path {
                 _nodes: path.clone(),
                 cost: total_cost + cost,
             });
+            progress.tick();
             path.pop();
         } else if !path.contains(next) {
             path.push(*next);
-            enumerate_paths_rec(nodes, *next, goal, path.clone(), total_cost + cost, paths);
+            enumerate_paths_rec(nodes, *next, goal, path.clone(), total_cost + cost, paths, progress)?;
             path.pop();
         }
     }
+    Ok(())
 }
@@ -0,0 +1,38 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::Problem;
+
+#[derive(Debug)]
+pub struct Stone {
+    pub position: (f64, f64, f64),
+    pub velocity: (f64, f64, f64),
+}
+
+/// Parses the hailstones, one per line, such as "19, 13, 30 @ -2, 1, -2"
+pub fn parse_stones(lines: &[String]) -> Result<Vec<Stone>, Problem> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| parse_stone(line).map_err(|message| Problem::line(i, message)))
+        .collect()
+}
+
+/// Parses a hailstone's position and velocity
+pub fn parse_stone(line: &str) -> Result<Stone, String> {
+    let (position, velocity) = line.split_once('@').ok_or("missing '@'")?;
+    Ok(Stone {
+        position: parse_vector(position)?,
+        velocity: parse_vector(velocity)?,
+    })
+}
+
+fn parse_vector(s: &str) -> Result<(f64, f64, f64), String> {
+    let components = s
+        .split(',')
+        .map(|token| token.trim().parse().map_err(|_| format!("{:?} is not a number", token.trim())))
+        .collect::<Result<Vec<f64>, String>>()?;
+    match components[..] {
+        [x, y, z] => Ok((x, y, z)),
+        _ => Err(format!("expected 3 components, found {}", components.len())),
+    }
+}
@@ -1,4 +1,10 @@
-use common::load;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{
+    args, debug, load,
+    svg::{Style, Svg},
+};
+use day24::{parse_stones, Stone};
 
 // Example data
 //const BOUNDS: ((f64, f64), (f64, f64)) = ((7.0, 27.0), (7.0, 27.0));
@@ -6,21 +12,18 @@ use common::load;
 // Real data
 const BOUNDS: ((f64, f64), (f64, f64)) = ((200000000000000.0, 400000000000000.0), (200000000000000.0, 400000000000000.0));
 
-#[derive(Debug)]
-struct Stone {
-    position: (f64, f64, f64),
-    velocity: (f64, f64, f64),
-}
-
 fn main() {
+    generate::run(300, generate);
+    validate::check(validate);
     println!("Day 24, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
 
     // Load the data
     let lines = load::lines().unwrap();
-    let stones = parse_stones(&lines);
+    let stones = validate::or_exit(parse_stones(&lines));
+    debug!("Stones: {}", stones.len());
 
     // Find all intersections
-    let intersection_count = stones
+    let intersections: Vec<(f64, f64)> = stones
         .iter()
         .enumerate()
         .flat_map(|(i, stone_i)| {
@@ -29,22 +32,97 @@ fn main() {
                 .skip(i + 1)
                 .filter_map(move |stone_j| intersects_xy(stone_i, stone_j))
         })
-        .count();
-    println!("Intersection count: {}", intersection_count);
+        .collect();
+
+    if let Some(path) = args::value("--svg") {
+        write_svg(&stones, &intersections, &path);
+    }
+
+    println!("Intersection count: {}", intersections.len());
+}
+
+// Returns the problems in the input. Each line is a hailstone such as "19, 13, 30 @ -2, 1, -2".
+fn validate(lines: &[String]) -> Vec<Problem> {
+    validate::each_line(lines, |line| {
+        let (position, velocity) = line.split_once('@').ok_or("missing '@'")?;
+        for vector in [position, velocity] {
+            let components = validate::numbers(vector, ',')?;
+            if components.len() != 3 {
+                return Err(format!("expected 3 components, found {}", components.len()));
+            }
+        }
+        Ok(())
+    })
+}
+
+// Returns random hailstones that a rock thrown from a random position with a random velocity hits, each at a different
+// time. The stones start near the test area. The size is the number of hailstones.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let rock = [0; 3].map(|_| rng.between(200_000_000_000_000, 400_000_000_000_000));
+    let rock_velocity = [0; 3].map(|_| rng.between(-300, 300));
+    debug!("Rock: {:?} @ {:?}", rock, rock_velocity);
+    let mut times: Vec<i64> = Vec::new();
+    while times.len() < size {
+        let t = rng.between(10_000_000_000, 1_000_000_000_000);
+        if !times.contains(&t) {
+            times.push(t);
+        }
+    }
+    times
+        .iter()
+        .map(|&t| {
+            let velocity = [0; 3].map(|_| rng.between(-300, 300));
+            let position: Vec<i64> = (0..3).map(|k| rock[k] + (rock_velocity[k] - velocity[k]) * t).collect();
+            format!(
+                "{}, {}, {} @ {}, {}, {}",
+                position[0], position[1], position[2], velocity[0], velocity[1], velocity[2]
+            )
+        })
+        .collect()
 }
 
-fn parse_stones(lines: &Vec<String>) -> Vec<Stone> {
-    let mut stones = Vec::new();
-    for line in lines {
-        let pv: Vec<&str> = line.split("@").collect();
-        let position: Vec<f64> = pv[0].split(",").map(|s| s.trim().parse().unwrap()).collect();
-        let velocity: Vec<f64> = pv[1].split(",").map(|s| s.trim().parse().unwrap()).collect();
-        stones.push(Stone {
-            position: (position[0], position[1], position[2]),
-            velocity: (velocity[0], velocity[1], velocity[2]),
-        });
+// Draws the test area, the part of each hailstone's path that is inside it, and the intersections
+fn write_svg(stones: &[Stone], intersections: &[(f64, f64)], path: &str) {
+    let min = (BOUNDS.0 .0, BOUNDS.1 .0);
+    let max = (BOUNDS.0 .1, BOUNDS.1 .1);
+    let mut svg = Svg::new(min, max);
+    svg.rect(min, max, &Style::new().fill("white").stroke("black", 1.0));
+    for stone in stones {
+        if let Some((from, to)) = clip_to_bounds(stone) {
+            svg.line(from, to, &Style::new().stroke("steelblue", 0.5));
+        }
+    }
+    let radius = svg.size().0 / 400.0;
+    for &intersection in intersections {
+        svg.circle(intersection, radius, &Style::new().fill("red"));
+    }
+    svg.save(path).unwrap_or_else(|e| panic!("Could not write \"{}\": {}", path, e));
+}
+
+// Returns the endpoints of the part of the stone's future path in the xy plane that is inside the bounds, if any
+fn clip_to_bounds(stone: &Stone) -> Option<((f64, f64), (f64, f64))> {
+    let mut t_min: f64 = 0.0;
+    let mut t_max: f64 = f64::INFINITY;
+    for (p, v, (low, high)) in [
+        (stone.position.0, stone.velocity.0, BOUNDS.0),
+        (stone.position.1, stone.velocity.1, BOUNDS.1),
+    ] {
+        if v == 0.0 {
+            if p < low || p > high {
+                return None;
+            }
+        } else {
+            let t0 = (low - p) / v;
+            let t1 = (high - p) / v;
+            t_min = t_min.max(t0.min(t1));
+            t_max = t_max.min(t0.max(t1));
+        }
+    }
+    if t_min > t_max {
+        return None;
     }
-    stones
+    let at = |t: f64| (stone.position.0 + t * stone.velocity.0, stone.position.1 + t * stone.velocity.1);
+    Some((at(t_min), at(t_max)))
 }
 
 //fn collides_xy(s1: &Stone, s2: &Stone) -> Option<(f64, (f64, f64))> {
@@ -0,0 +1,47 @@
+//! Parsing of the day's input. It is a library so that the fuzz targets can use it.
+
+use common::validate::Problem;
+use std::collections::HashMap;
+
+/// An undirected graph of node ids, with the neighbors of each node
+pub type Graph = HashMap<usize, Vec<usize>>;
+
+/// Parses the connections of each component, one per line, such as "jqt: rhn xhk nvd". It returns the graph and the
+/// name of each node id.
+pub fn parse_graph(lines: &[String]) -> Result<(Graph, Vec<&str>), Problem> {
+    let mut graph = HashMap::new();
+    let mut node_name_map = Vec::new();
+    let mut node_id_map = HashMap::new();
+
+    for (i, line) in lines.iter().enumerate() {
+        let (key, neighbors) = line.split_once(":").ok_or_else(|| Problem::line(i, "missing ':'"))?;
+        let key_id = register_node(key.trim(), &mut node_id_map, &mut node_name_map);
+        // A component with no connections is still a node
+        graph.entry(key_id).or_insert_with(Vec::new);
+
+        // Add the neighbors for the key and add key as a neighbor for each neighbor (expecting duplicates)
+        for neighbor in neighbors.split_whitespace() {
+            let neighbor_id = register_node(neighbor, &mut node_id_map, &mut node_name_map);
+            graph.entry(key_id).or_insert_with(Vec::new).push(neighbor_id);
+            graph.entry(neighbor_id).or_insert_with(Vec::new).push(key_id);
+        }
+    }
+
+    // For each key, sort the neighbors and remove duplicates
+    for neighbors in graph.values_mut() {
+        neighbors.sort_unstable();
+        neighbors.dedup();
+    }
+    Ok((graph, node_name_map))
+}
+
+fn register_node<'a>(name: &'a str, node_id_map: &mut HashMap<&'a str, usize>, node_name_map: &mut Vec<&'a str>) -> usize {
+    if let Some(&id) = node_id_map.get(name) {
+        id
+    } else {
+        let id = node_name_map.len();
+        node_name_map.push(name);
+        node_id_map.insert(name, id);
+        id
+    }
+}
@@ -1,12 +1,19 @@
-use common::load;
-use std::collections::HashMap;
+use common::generate::{self, Rng};
+use common::validate::{self, Problem};
+use common::{args, cancel, debug, dot, info, load};
+use day25::parse_graph;
+use std::collections::{HashMap, HashSet};
 
 fn main() {
+    cancel::start();
+    generate::run(700, generate);
+    validate::check(validate);
     println!("Day 25, part {}", if cfg!(feature = "part2") { "2" } else { "1" });
 
     // Load the data
     let lines = load::lines().unwrap();
-    let mut graph = parse_graph(&lines);
+    let (mut graph, names) = validate::or_exit(parse_graph(&lines));
+    let original = graph.clone();
 
     // Ideally, you would find the three edges that when removed would split the graph into two disjoint graphs.
     // However, the cost of finding the three edges is too high, so we try a different approach. Since the farthest
@@ -19,50 +26,100 @@ fn main() {
 
     let node0 = *graph.keys().next().unwrap();
     while !any_node_unreachable_from(&graph, node0) {
+        cancel::check().unwrap_or_else(cancel::exit);
         let longest_path: Vec<usize> = find_path_from_farthest_node(&graph, node0);
+        debug!("Removing a path of {} edges", longest_path.len() - 1);
         for w in longest_path.windows(2) {
             remove_edge(&mut graph, &(w[0], w[1]));
         }
     }
     let a = count_reachable_nodes(&graph, node0);
     let b = graph.len() - a;
+    info!("Groups: {} and {}", a, b);
+
+    if let Some(path) = args::value("--dot") {
+        write_dot(&original, &names, &reachable_nodes(&graph, node0), &path);
+    }
+
     println!("Part 1: {}", a * b);
 }
 
-fn parse_graph(lines: &Vec<String>) -> HashMap<usize, Vec<usize>> {
-    let mut graph = HashMap::new();
-    let mut node_name_map = Vec::new();
-    let mut node_id_map = HashMap::new();
-
-    for line in lines {
-        let (key, neighbors) = line.split_once(":").unwrap();
-        let key_id = register_node(key.trim(), &mut node_id_map, &mut node_name_map);
+// Returns the problems in the input. Each line is a component and the components it is connected to, such as
+// "jqt: rhn xhk nvd".
+fn validate(lines: &[String]) -> Vec<Problem> {
+    let is_name = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_alphanumeric());
+    validate::each_line(lines, |line| {
+        let (name, connections) = line.split_once(": ").ok_or("missing \": \"")?;
+        if !is_name(name) {
+            return Err(format!("{:?} is not a component name", name));
+        }
+        let connections: Vec<&str> = connections.split(' ').collect();
+        match connections.iter().find(|c| !is_name(c)) {
+            Some(c) => Err(format!("{:?} is not a component name", c)),
+            None => Ok(()),
+        }
+    })
+}
 
-        // Add the neighbors for the key and add key as a neighbor for each neighbor (expecting duplicates)
-        for neighbor in neighbors.trim().split_whitespace() {
-            let neighbor_id = register_node(neighbor, &mut node_id_map, &mut node_name_map);
-            graph.entry(key_id).or_insert_with(Vec::new).push(neighbor_id);
-            graph.entry(neighbor_id).or_insert_with(Vec::new).push(key_id);
+// Returns two random, well connected groups of components joined by exactly three wires. The size is the number of
+// components in each group.
+fn generate(rng: &mut Rng, size: usize) -> Vec<String> {
+    let size = size.max(5);
+    let names = rng.names(2 * size, 3, "abcdefghijklmnopqrstuvwxyz", &[]);
+    let mut wires: HashSet<(usize, usize)> = HashSet::new();
+    for group in [0, size] {
+        // A ring keeps each group connected, and chords make it hard to cut
+        for i in 0..size {
+            let j = (i + 1) % size;
+            wires.insert((group + i.min(j), group + i.max(j)));
+            for _ in 0..3 {
+                let j = rng.below(size);
+                if j != i {
+                    wires.insert((group + i.min(j), group + i.max(j)));
+                }
+            }
+        }
+    }
+    for _ in 0..3 {
+        loop {
+            let wire = (rng.below(size), size + rng.below(size));
+            if wires.insert(wire) {
+                break;
+            }
         }
     }
 
-    // For each key, sort the neighbors and remove duplicates
-    for neighbors in graph.values_mut() {
-        neighbors.sort_unstable();
-        neighbors.dedup();
+    let mut connections: Vec<Vec<&str>> = vec![Vec::new(); 2 * size];
+    for &(a, b) in &wires {
+        connections[a].push(&names[b]);
     }
-    graph
+    let mut lines: Vec<String> = connections
+        .iter()
+        .enumerate()
+        .filter(|(_, connected)| !connected.is_empty())
+        .map(|(i, connected)| format!("{}: {}", names[i], connected.join(" ")))
+        .collect();
+    rng.shuffle(&mut lines);
+    lines
 }
 
-fn register_node<'a>(name: &'a str, node_id_map: &mut HashMap<&'a str, usize>, node_name_map: &mut Vec<&'a str>) -> usize {
-    if let Some(&id) = node_id_map.get(name) {
-        id
-    } else {
-        let id = node_name_map.len();
-        node_name_map.push(name);
-        node_id_map.insert(name, id);
-        id
+// Writes the wiring as a graph with each group in its own color and the edges between the groups highlighted
+fn write_dot(graph: &HashMap<usize, Vec<usize>>, names: &[&str], group: &[bool], path: &str) {
+    let mut dot = dot::Graph::undirected();
+    for (id, name) in names.iter().enumerate() {
+        let color = if group[id] { "lightblue" } else { "lightgreen" };
+        dot.node(name, &[("style", "filled"), ("fillcolor", color)]);
+    }
+    for node in 0..names.len() {
+        for &neighbor in graph.get(&node).into_iter().flatten().filter(|&&neighbor| neighbor > node) {
+            if group[node] != group[neighbor] {
+                dot.edge(names[node], names[neighbor], &[("color", "red"), ("penwidth", "3")]);
+            } else {
+                dot.edge(names[node], names[neighbor], &[]);
+            }
+        }
     }
+    dot.save(path).unwrap_or_else(|e| panic!("Could not write \"{}\": {}", path, e));
 }
 
 /// Returns the path from the farthest node back to the start node.
@@ -74,7 +131,7 @@ fn find_path_from_farthest_node(graph: &HashMap<usize, Vec<usize>>, start: usize
     while !next_span.is_empty() {
         let span = std::mem::take(&mut next_span);
         for &node in &span {
-            for &neighbor in &graph[&node] {
+            for &neighbor in graph.get(&node).into_iter().flatten() {
                 if !visited[neighbor] {
                     visited[neighbor] = true;
                     predecessors[neighbor] = node;
@@ -102,15 +159,20 @@ fn remove_edge(graph: &mut HashMap<usize, Vec<usize>>, edge: &(usize, usize)) {
 }
 
 fn count_reachable_nodes(graph: &HashMap<usize, Vec<usize>>, start: usize) -> usize {
+    reachable_nodes(graph, start).iter().filter(|&&v| v).count()
+}
+
+/// Returns a flag for each node that is true if the node is reachable from the start
+fn reachable_nodes(graph: &HashMap<usize, Vec<usize>>, start: usize) -> Vec<bool> {
     let mut visited = vec![false; graph.len()];
     let mut stack = vec![start];
     while let Some(node) = stack.pop() {
         if !visited[node] {
             visited[node] = true;
-            stack.extend(graph[&node].iter());
+            stack.extend(graph.get(&node).into_iter().flatten());
         }
     }
-    visited.iter().filter(|&&v| v).count()
+    visited
 }
 
 fn any_node_unreachable_from(graph: &HashMap<usize, Vec<usize>>, start: usize) -> bool {
@@ -0,0 +1,14 @@
+#![no_main]
+
+use common::load;
+use libfuzzer_sys::fuzz_target;
+
+// The input is a vocabulary followed by the lines it is used on
+fuzz_target!(|input: &str| {
+    let lines = load::parse_lines(input);
+    if let Ok(vocabulary) = day01::Vocabulary::parse(&lines) {
+        for line in &lines {
+            let _ = day01::calibration_value(&vocabulary, line);
+        }
+    }
+});
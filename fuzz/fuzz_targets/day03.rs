@@ -0,0 +1,8 @@
+#![no_main]
+
+use common::load;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = day03::Schematic::parse(&load::parse_lines(input));
+});
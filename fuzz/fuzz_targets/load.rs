@@ -0,0 +1,11 @@
+#![no_main]
+
+use common::load;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = load::parse_lines(input);
+    let _ = load::parse_comma_separated_values(input);
+    let _ = load::parse_map(input);
+    let _ = load::parse_numbers_map(input);
+});